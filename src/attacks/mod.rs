@@ -0,0 +1,239 @@
+pub mod attacks {
+    use std::sync::OnceLock;
+
+    use crate::{
+        bitboard::bitboard::{bit, color_index, square_of, Bitboard, FILE_A, FILE_H, RANK_1, RANK_8},
+        components::chess::Color,
+        moves::moves::{
+            square_index, BISHOP_MOVES_FILE, BISHOP_MOVES_RANK, KING_MOVES_FILE, KING_MOVES_RANK,
+            KNIGHT_MOVES_FILE, KNIGHT_MOVES_RANK, ROOK_MOVES_FILE, ROOK_MOVES_RANK,
+        },
+    };
+
+    // the relevant occupancy of a slider on a square is multiplied by the magic, the top
+    // bits of the product index the slice of the attack table belonging to that square
+    #[derive(Default, Clone, Copy)]
+    struct Magic {
+        mask: Bitboard,
+        magic: u64,
+        shift: u32,
+        offset: usize,
+    }
+
+    impl Magic {
+        fn index(&self, occupied: Bitboard) -> usize {
+            self.offset + ((occupied & self.mask).wrapping_mul(self.magic) >> self.shift) as usize
+        }
+    }
+
+    struct Tables {
+        king: [Bitboard; 64],
+        knight: [Bitboard; 64],
+        pawn: [[Bitboard; 64]; 2],
+        rook: [Magic; 64],
+        bishop: [Magic; 64],
+        rook_attacks: Vec<Bitboard>,
+        bishop_attacks: Vec<Bitboard>,
+    }
+
+    static TABLES: OnceLock<Tables> = OnceLock::new();
+
+    fn tables() -> &'static Tables {
+        TABLES.get_or_init(Tables::build)
+    }
+
+    // builds the tables up front so the first search or perft does not pay for it
+    pub fn init() {
+        tables();
+    }
+
+    pub fn king_attacks(index: usize) -> Bitboard {
+        tables().king[index]
+    }
+
+    pub fn knight_attacks(index: usize) -> Bitboard {
+        tables().knight[index]
+    }
+
+    pub fn pawn_attacks(index: usize, color: &Color) -> Bitboard {
+        tables().pawn[color_index(color)][index]
+    }
+
+    pub fn rook_attacks(index: usize, occupied: Bitboard) -> Bitboard {
+        let tables = tables();
+        tables.rook_attacks[tables.rook[index].index(occupied)]
+    }
+
+    pub fn bishop_attacks(index: usize, occupied: Bitboard) -> Bitboard {
+        let tables = tables();
+        tables.bishop_attacks[tables.bishop[index].index(occupied)]
+    }
+
+    pub fn queen_attacks(index: usize, occupied: Bitboard) -> Bitboard {
+        rook_attacks(index, occupied) | bishop_attacks(index, occupied)
+    }
+
+    fn on_board(rank: i8, file: i8) -> bool {
+        (1..=8).contains(&rank) && (1..=8).contains(&file)
+    }
+
+    fn offset_attacks(index: usize, files: &[i8], ranks: &[i8]) -> Bitboard {
+        let (rank, file) = square_of(index);
+        let mut attacks = 0;
+        for (file_offset, rank_offset) in files.iter().zip(ranks) {
+            let new_rank = rank as i8 + rank_offset;
+            let new_file = file as i8 + file_offset;
+            if on_board(new_rank, new_file) {
+                attacks |= bit(square_index(new_rank as u8, new_file as u8));
+            }
+        }
+        attacks
+    }
+
+    // walks every ray square by square, only used to fill the tables
+    fn sliding_attacks(index: usize, occupied: Bitboard, files: &[i8], ranks: &[i8]) -> Bitboard {
+        let (rank, file) = square_of(index);
+        let mut attacks = 0;
+        for (file_offset, rank_offset) in files.iter().zip(ranks) {
+            for shift in 1..8 {
+                let new_rank = rank as i8 + shift * rank_offset;
+                let new_file = file as i8 + shift * file_offset;
+                if !on_board(new_rank, new_file) {
+                    break;
+                }
+
+                let target = bit(square_index(new_rank as u8, new_file as u8));
+                attacks |= target;
+                if occupied & target != 0 {
+                    break;
+                }
+            }
+        }
+        attacks
+    }
+
+    // xorshift64* as used by Stockfish for its magic search, seeded per rank so that
+    // every square finds its magic after a handful of tries
+    struct Prng(u64);
+
+    impl Prng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 >> 12;
+            self.0 ^= self.0 << 25;
+            self.0 ^= self.0 >> 27;
+            self.0.wrapping_mul(2685821657736338717)
+        }
+
+        fn sparse(&mut self) -> u64 {
+            self.next() & self.next() & self.next()
+        }
+    }
+
+    const MAGIC_SEEDS: [u64; 8] = [728, 10316, 55013, 32803, 12281, 15100, 16645, 255];
+
+    fn find_magics(
+        files: &[i8],
+        ranks: &[i8],
+        magics: &mut [Magic; 64],
+        table: &mut Vec<Bitboard>,
+    ) {
+        let mut occupancies: Vec<Bitboard> = Vec::with_capacity(4096);
+        let mut references: Vec<Bitboard> = Vec::with_capacity(4096);
+        let mut epoch: Vec<u32> = vec![0; 4096];
+        let mut attempt: u32 = 0;
+
+        for index in 0..64 {
+            // the edges only matter when the slider stands on them
+            let edges = ((RANK_1 | RANK_8) & !(RANK_1 << (8 * (index / 8))))
+                | ((FILE_A | FILE_H) & !(FILE_A << (index % 8)));
+            let mask = sliding_attacks(index, 0, files, ranks) & !edges;
+            let bits = mask.count_ones();
+
+            // carry-rippler trick to visit every subset of the mask
+            occupancies.clear();
+            references.clear();
+            let mut subset: Bitboard = 0;
+            loop {
+                occupancies.push(subset);
+                references.push(sliding_attacks(index, subset, files, ranks));
+                subset = subset.wrapping_sub(mask) & mask;
+                if subset == 0 {
+                    break;
+                }
+            }
+
+            let offset = table.len();
+            table.resize(offset + occupancies.len(), 0);
+            let mut prng = Prng(MAGIC_SEEDS[index / 8]);
+
+            let magic = loop {
+                let candidate = loop {
+                    let candidate = prng.sparse();
+                    if (mask.wrapping_mul(candidate) >> 56).count_ones() >= 6 {
+                        break candidate;
+                    }
+                };
+
+                let magic = Magic {
+                    mask,
+                    magic: candidate,
+                    shift: 64 - bits,
+                    offset,
+                };
+
+                attempt += 1;
+                let mut collision = false;
+                for (occupied, reference) in occupancies.iter().zip(&references) {
+                    let slot = magic.index(*occupied) - offset;
+                    if epoch[slot] < attempt {
+                        epoch[slot] = attempt;
+                        table[offset + slot] = *reference;
+                    } else if table[offset + slot] != *reference {
+                        collision = true;
+                        break;
+                    }
+                }
+
+                if !collision {
+                    break magic;
+                }
+            };
+            magics[index] = magic;
+        }
+    }
+
+    impl Tables {
+        fn build() -> Tables {
+            let mut tables = Tables {
+                king: [0; 64],
+                knight: [0; 64],
+                pawn: [[0; 64]; 2],
+                rook: [Magic::default(); 64],
+                bishop: [Magic::default(); 64],
+                rook_attacks: vec![],
+                bishop_attacks: vec![],
+            };
+
+            for index in 0..64 {
+                tables.king[index] = offset_attacks(index, &KING_MOVES_FILE, &KING_MOVES_RANK);
+                tables.knight[index] = offset_attacks(index, &KNIGHT_MOVES_FILE, &KNIGHT_MOVES_RANK);
+                tables.pawn[0][index] = offset_attacks(index, &[1, -1], &[1, 1]);
+                tables.pawn[1][index] = offset_attacks(index, &[1, -1], &[-1, -1]);
+            }
+
+            find_magics(
+                &ROOK_MOVES_FILE,
+                &ROOK_MOVES_RANK,
+                &mut tables.rook,
+                &mut tables.rook_attacks,
+            );
+            find_magics(
+                &BISHOP_MOVES_FILE,
+                &BISHOP_MOVES_RANK,
+                &mut tables.bishop,
+                &mut tables.bishop_attacks,
+            );
+            tables
+        }
+    }
+}
@@ -0,0 +1,94 @@
+pub mod bitboard {
+    use crate::components::chess::{Color, PieceType};
+
+    // bit n stands for the square with index n, a1 = 0, b1 = 1 ... h8 = 63
+    pub type Bitboard = u64;
+
+    pub const EMPTY: Bitboard = 0;
+    pub const FILE_A: Bitboard = 0x0101_0101_0101_0101;
+    pub const FILE_H: Bitboard = FILE_A << 7;
+    pub const RANK_1: Bitboard = 0xff;
+    pub const RANK_2: Bitboard = RANK_1 << 8;
+    pub const RANK_4: Bitboard = RANK_1 << 24;
+    pub const RANK_5: Bitboard = RANK_1 << 32;
+    pub const RANK_7: Bitboard = RANK_1 << 48;
+    pub const RANK_8: Bitboard = RANK_1 << 56;
+
+    pub const PIECE_TYPES: [PieceType; 6] = [
+        PieceType::King,
+        PieceType::Queen,
+        PieceType::Pawn,
+        PieceType::Bishop,
+        PieceType::Rook,
+        PieceType::Knight,
+    ];
+
+    pub fn bit(index: usize) -> Bitboard {
+        1 << index
+    }
+
+    // removes the lowest set bit and returns its index, the bitboard must not be empty
+    pub fn pop_lsb(bitboard: &mut Bitboard) -> usize {
+        let index = bitboard.trailing_zeros() as usize;
+        *bitboard &= *bitboard - 1;
+        index
+    }
+
+    pub fn lsb(bitboard: Bitboard) -> usize {
+        bitboard.trailing_zeros() as usize
+    }
+
+    pub fn count(bitboard: Bitboard) -> u32 {
+        bitboard.count_ones()
+    }
+
+    pub fn rank_of(index: usize) -> u8 {
+        (index >> 3) as u8 + 1
+    }
+
+    pub fn file_of(index: usize) -> u8 {
+        (index & 7) as u8 + 1
+    }
+
+    // (rank, file) the way moves store their squares
+    pub fn square_of(index: usize) -> (u8, u8) {
+        (rank_of(index), file_of(index))
+    }
+
+    pub fn color_index(color: &Color) -> usize {
+        match color {
+            Color::White => 0,
+            _ => 1,
+        }
+    }
+
+    pub fn piece_index(piece_type: &PieceType) -> usize {
+        match piece_type {
+            PieceType::King => 0,
+            PieceType::Queen => 1,
+            PieceType::Pawn => 2,
+            PieceType::Bishop => 3,
+            PieceType::Rook => 4,
+            PieceType::Knight => 5,
+            PieceType::Empty => 6,
+        }
+    }
+
+    // iterates over the indices of the set bits, lowest first
+    pub struct Squares(pub Bitboard);
+
+    impl Iterator for Squares {
+        type Item = usize;
+
+        fn next(&mut self) -> Option<usize> {
+            if self.0 == EMPTY {
+                return None;
+            }
+            Some(pop_lsb(&mut self.0))
+        }
+    }
+
+    pub fn squares(bitboard: Bitboard) -> Squares {
+        Squares(bitboard)
+    }
+}
@@ -1,8 +1,8 @@
 
 
 pub mod chess {
-    use std::{f32::consts::E, fmt::{self}, io::Empty, vec};
-    use crate::{fen::fen::{self, build_board_from_fen, Fen}, moves::moves::{get_protected_squares, Move}};
+    use std::fmt::{self};
+    use crate::{bitboard::bitboard::{bit, color_index, file_of, lsb, piece_index, rank_of, Bitboard}, fen::fen::{self, build_board_from_fen, build_fen, build_fen_with, get_castling_from_string, CastlingNotation, get_en_passant_from_string, parse_fen, Fen, FenError}, moves::moves::{get_squares_seen, square_index, CastleSide, Move}, zobrist::zobrist::{castling_key, compute_hash, en_passant_key, piece_key, turn_key}};
 
     #[derive(Debug)]
     pub enum Error {
@@ -59,8 +59,8 @@ pub mod chess {
         }
     }
     
-    #[derive(Debug)]
-    #[derive(PartialEq)]
+    #[derive(Debug, Clone, Copy)]
+    #[derive(PartialEq, Eq)]
     pub enum Color {
         White,
         Black,
@@ -69,8 +69,8 @@ pub mod chess {
 
 
 
-    #[derive(Debug)]
-    #[derive(PartialEq)]
+    #[derive(Debug, Clone, Copy)]
+    #[derive(PartialEq, Eq)]
     pub enum PieceType {
         King,
         Queen,
@@ -80,25 +80,92 @@ pub mod chess {
         Knight,
         Empty,
     }
-    #[derive(Debug)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     pub struct Square {
         pub piece: Piece,
         pub rank : u8 ,
         pub file : u8,
     }
 
-    #[derive(Debug)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     pub struct Piece {
         pub piece_type: PieceType,
         pub color: Color,
     }
 
+    impl Color {
+        pub fn opposite(&self) -> Color {
+            match self {
+                Color::White => Color::Black,
+                Color::Black => Color::White,
+                Color::NoColor => Color::NoColor,
+            }
+        }
+    }
+
+    impl Piece {
+        pub fn empty() -> Piece {
+            Piece {
+                piece_type: PieceType::Empty,
+                color: Color::NoColor,
+            }
+        }
+    }
+
+    pub const WHITE_KING_SIDE: u8 = 1;
+    pub const WHITE_QUEEN_SIDE: u8 = 2;
+    pub const BLACK_KING_SIDE: u8 = 4;
+    pub const BLACK_QUEEN_SIDE: u8 = 8;
+    pub const CASTLING_RIGHTS: [u8; 4] = [WHITE_KING_SIDE, WHITE_QUEEN_SIDE, BLACK_KING_SIDE, BLACK_QUEEN_SIDE];
+
+    pub fn castling_right(color : Color, side : CastleSide) -> u8 {
+        match (color, side) {
+            (Color::White, CastleSide::KingSide) => WHITE_KING_SIDE,
+            (Color::White, CastleSide::QueenSide) => WHITE_QUEEN_SIDE,
+            (_, CastleSide::KingSide) => BLACK_KING_SIDE,
+            (_, CastleSide::QueenSide) => BLACK_QUEEN_SIDE,
+        }
+    }
+
+    // everything make_move overwrites that can not be recomputed from the move itself
+    #[derive(Debug, Clone, Copy)]
+    pub struct Undo {
+        pub captured: Piece,
+        pub castling: u8,
+        pub en_passant: Option<(u8, u8)>,
+        pub half_moves: u32,
+        pub squares_seen_by_white : u64,
+        pub squares_seen_by_black : u64,
+        pub hash : u64,
+        // the polyglot key of the position, which repetitions compare
+        pub key : u64,
+        // pushed by make_null_move, no entry in moves belongs to it
+        pub null : bool,
+    }
+
+    #[derive(Clone)]
     pub struct Board {
-        pub squares: Vec<Square>,
+        // one bitboard per piece type (indexed by bitboard::piece_index) and per color,
+        // the mailbox answers "what is on this square" without scanning them
+        pub pieces : [Bitboard; 6],
+        pub colors : [Bitboard; 2],
+        pub occupied : Bitboard,
+        pub mailbox : [Piece; 64],
         pub squares_seen_by_white : u64,
         pub squares_seen_by_black : u64,
         pub moves : Vec<Move>,
-        pub fen : Fen,
+        pub turn : Color,
+        pub castling : u8,
+        // file of the rook each castling right refers to, in the order of CASTLING_RIGHTS
+        pub castling_rooks : [u8; 4],
+        // castling moves are written as the king taking its own rook
+        pub chess960 : bool,
+        pub en_passant : Option<(u8, u8)>,
+        pub half_moves : u32,
+        pub full_moves : u32,
+        pub undo_stack : Vec<Undo>,
+        // zobrist key of the position, see zobrist::compute_hash
+        pub hash : u64,
     }
 
     impl Board {
@@ -120,77 +187,293 @@ pub mod chess {
             sqaures
         }
 
-        pub fn build_from_fen(fen : String) -> Board {
-            let fen_vector : Vec<&str>  = fen.split_ascii_whitespace().into_iter().collect();
-            let fen = Fen {
-                pieces : fen_vector[0].to_string(),
-                turn : fen_vector[1].chars().next().unwrap(),
-                castling : fen_vector[2].to_string(),
-                en_passant : fen_vector[3].to_string(),
-                half_moves : fen_vector[4].parse().unwrap(),
-                full_moves : fen_vector[5].parse().unwrap()
-            };
-            let squares = build_board_from_fen(&fen.pieces);
-            let mut squares_seen_by_black = 0;
-            let mut squares_seen_by_white = 0;
-            for square in &squares {
-                if square.piece.piece_type == PieceType::Empty {
-                    continue;
-                }
+        pub fn build_from_fen(fen : String) -> Result<Board, FenError> {
+            Board::build_from_fen_fields(&parse_fen(&fen)?)
+        }
 
-                if square.piece.color == Color::Black {
-                    squares_seen_by_black |= get_protected_squares(&square, &squares);
-                }else {
-                    squares_seen_by_white |= get_protected_squares(&square, &squares);
-                }
+        pub fn build() -> Board {
+            Board::build_from_fen_fields(&fen::start_fen()).expect("the start position is a valid fen")
+        }
 
-            }
-            Board {
-                squares : build_board_from_fen(&fen.pieces),
-                squares_seen_by_white,
-                squares_seen_by_black,
+        // start position index of Fischer random chess, see fen::chess960_back_rank
+        pub fn build_chess960(index : u16) -> Option<Board> {
+            let mut board = Board::build_from_fen_fields(&fen::chess960_start_fen(index)?).ok()?;
+            board.chess960 = true;
+            Some(board)
+        }
+
+        fn build_from_fen_fields(fen : &Fen) -> Result<Board, FenError> {
+            let mut board = Board::from_squares(&build_board_from_fen(&fen.pieces)?);
+            board.turn = if fen.turn == 'w' { Color::White } else { Color::Black };
+            (board.castling, board.castling_rooks) = get_castling_from_string(&fen.castling, &board)?;
+            board.en_passant = get_en_passant_from_string(&fen.en_passant, &board)?;
+            board.half_moves = fen.half_moves;
+            board.full_moves = fen.full_moves;
+            board.hash = compute_hash(&board);
+            Ok(board)
+        }
+
+        // a board with the pieces of the square list, white to move and no castling rights
+        pub fn from_squares(squares : &[Square]) -> Board {
+            let mut board = Board {
+                pieces : [0; 6],
+                colors : [0; 2],
+                occupied : 0,
+                mailbox : [Piece::empty(); 64],
+                squares_seen_by_white : 0,
+                squares_seen_by_black : 0,
                 moves : vec![],
-                fen,
+                turn : Color::White,
+                castling : 0,
+                castling_rooks : [8, 1, 8, 1],
+                chess960 : false,
+                en_passant : None,
+                half_moves : 0,
+                full_moves : 1,
+                undo_stack : vec![],
+                hash : 0,
+            };
+            for square in squares {
+                if square.piece.piece_type != PieceType::Empty {
+                    board.put_piece(square_index(square.rank, square.file), square.piece);
+                }
             }
+            board.update_squares_seen();
+            board.hash = compute_hash(&board);
+            board
         }
 
-        pub fn build() -> Board {
-            let mut squares_seen_by_white: u64 = 0;
-            let mut squares_seen_by_black: u64 = 0;
-            
-            let fen : Fen = fen::start_fen();
-            let squares: Vec<Square> = build_board_from_fen(&fen.pieces);
-            for square in &squares {
-                if square.piece.piece_type == PieceType::Empty {
-                    continue;
-                }
+        // the position as the list of 64 squares, a1 first and h8 last
+        pub fn squares(&self) -> Vec<Square> {
+            (0..64)
+                .map(|index| Square {
+                    piece : self.mailbox[index],
+                    rank : rank_of(index),
+                    file : file_of(index),
+                })
+                .collect()
+        }
 
-                if square.piece.color == Color::Black {
-                    squares_seen_by_black |= get_protected_squares(&square, &squares);
-                }else {
-                    squares_seen_by_white |= get_protected_squares(&square, &squares);
-                }
+        pub fn fen(&self) -> Fen {
+            build_fen(self)
+        }
+
+        // the fen of the current position, build_from_fen reads it back to an equal board
+        pub fn to_fen(&self) -> String {
+            self.fen().to_string()
+        }
+
+        // same as to_fen but with the castling rooks always named by their file
+        pub fn to_shredder_fen(&self) -> String {
+            build_fen_with(self, CastlingNotation::Shredder).to_string()
+        }
+
+        pub fn piece_at(&self, index : usize) -> Piece {
+            self.mailbox[index]
+        }
 
+        pub fn put_piece(&mut self, index : usize, piece : Piece) {
+            self.pieces[piece_index(&piece.piece_type)] |= bit(index);
+            self.colors[color_index(&piece.color)] |= bit(index);
+            self.occupied |= bit(index);
+            self.mailbox[index] = piece;
+            self.hash ^= piece_key(&piece, index);
+        }
+
+        pub fn remove_piece(&mut self, index : usize) -> Piece {
+            let piece = self.mailbox[index];
+            if piece.piece_type == PieceType::Empty {
+                return piece;
             }
+            self.pieces[piece_index(&piece.piece_type)] &= !bit(index);
+            self.colors[color_index(&piece.color)] &= !bit(index);
+            self.occupied &= !bit(index);
+            self.mailbox[index] = Piece::empty();
+            self.hash ^= piece_key(&piece, index);
+            piece
+        }
 
-            Board {
-                squares,
-                moves : vec![] , 
-                squares_seen_by_black, 
-                squares_seen_by_white, 
-                fen,
+        pub fn pieces_of(&self, piece_type : PieceType, color : Color) -> Bitboard {
+            self.pieces[piece_index(&piece_type)] & self.colors[color_index(&color)]
+        }
+
+        pub fn king_square(&self, color : Color) -> Option<usize> {
+            let king = self.pieces_of(PieceType::King, color);
+            if king == 0 {
+                None
+            } else {
+                Some(lsb(king))
             }
         }
 
+        // the square of the rook a castling right refers to
+        pub fn castling_rook(&self, right : u8) -> (u8, u8) {
+            let rank = if right & (WHITE_KING_SIDE | WHITE_QUEEN_SIDE) != 0 { 1 } else { 8 };
+            (rank, self.castling_rooks[right.trailing_zeros() as usize])
+        }
+
+        pub fn update_squares_seen(&mut self) {
+            self.squares_seen_by_white = get_squares_seen(self, &Color::White);
+            self.squares_seen_by_black = get_squares_seen(self, &Color::Black);
+        }
+
+        // plays the move on the board, the move is expected to be one of get_legal_moves
+        pub fn make_move(& mut self , mv : Move) {
+            let source = square_index(mv.source.0, mv.source.1);
+            let destination = square_index(mv.destination.0, mv.destination.1);
+            let pawn_move = self.mailbox[source].piece_type == PieceType::Pawn;
+
+            // a pawn taking on the en passant square takes the pawn behind it
+            let taken = if pawn_move && self.en_passant == Some(mv.destination) {
+                square_index(mv.source.0, mv.destination.1)
+            } else {
+                destination
+            };
+
+            // castling never captures, in chess960 the king may even land on its own rook
+            let castling_rook = self.castling_rook_squares(&mv);
+            let hash = self.hash;
+            let captured = if castling_rook.is_some() { Piece::empty() } else { self.remove_piece(taken) };
+            self.undo_stack.push(Undo {
+                captured,
+                castling : self.castling,
+                en_passant : self.en_passant,
+                half_moves : self.half_moves,
+                squares_seen_by_white : self.squares_seen_by_white,
+                squares_seen_by_black : self.squares_seen_by_black,
+                hash,
+                key : self.polyglot_key(),
+                null : false,
+            });
+
+            let piece = self.remove_piece(source);
+            let rook = castling_rook.map(|(rook_source, _)| self.remove_piece(rook_source));
+            self.put_piece(destination, match mv.promotion {
+                Some(piece_type) => Piece { piece_type, color : piece.color },
+                None => piece,
+            });
+            if let (Some(rook), Some((_, rook_destination))) = (rook, castling_rook) {
+                self.put_piece(rook_destination, rook);
+            }
+
+            self.hash ^= en_passant_key(self.en_passant) ^ castling_key(self.castling);
+            self.en_passant = if pawn_move && mv.source.0.abs_diff(mv.destination.0) == 2 {
+                Some(((mv.source.0 + mv.destination.0) / 2, mv.source.1))
+            } else {
+                None
+            };
+            let mut lost = self.castling_lost_on(mv.source) | self.castling_lost_on(mv.destination);
+            if piece.piece_type == PieceType::King {
+                lost |= castling_right(piece.color, CastleSide::KingSide) | castling_right(piece.color, CastleSide::QueenSide);
+            }
+            self.castling &= !lost;
+            self.hash ^= en_passant_key(self.en_passant) ^ castling_key(self.castling);
+            self.half_moves = if pawn_move || mv.takes { 0 } else { self.half_moves + 1 };
+            if self.turn == Color::Black {
+                self.full_moves += 1;
+            }
+            self.hash ^= turn_key(self.turn);
+            self.turn = self.turn.opposite();
+            self.hash ^= turn_key(self.turn);
 
-        pub fn add_move(& mut self , mv : Move) ->() {
+            self.update_squares_seen();
             self.moves.push(mv);
         }
 
-    }
+        // the move that led to the position, none right after a null move
+        pub fn last_move(&self) -> Option<&Move> {
+            match self.undo_stack.last() {
+                Some(undo) if !undo.null => self.moves.last(),
+                _ => None,
+            }
+        }
 
+        // takes back the last move played with make_move, restoring the exact previous state
+        pub fn unmake_move(& mut self) -> Option<Move> {
+            let mv = self.moves.pop()?;
+            let undo = self.undo_stack.pop()?;
 
-}
+            let source = square_index(mv.source.0, mv.source.1);
+            let destination = square_index(mv.destination.0, mv.destination.1);
+            let mut piece = self.remove_piece(destination);
+            if mv.promotion.is_some() {
+                piece.piece_type = PieceType::Pawn;
+            }
 
+            let castling_rook = self.castling_rook_squares(&mv)
+                .map(|(rook_source, rook_destination)| (rook_source, self.remove_piece(rook_destination)));
 
+            let taken = if piece.piece_type == PieceType::Pawn && undo.en_passant == Some(mv.destination) {
+                square_index(mv.source.0, mv.destination.1)
+            } else {
+                destination
+            };
+            if undo.captured.piece_type != PieceType::Empty {
+                self.put_piece(taken, undo.captured);
+            }
+            self.put_piece(source, piece);
+            if let Some((rook_source, rook)) = castling_rook {
+                self.put_piece(rook_source, rook);
+            }
+
+            self.turn = self.turn.opposite();
+            if self.turn == Color::Black {
+                self.full_moves -= 1;
+            }
+            self.castling = undo.castling;
+            self.en_passant = undo.en_passant;
+            self.half_moves = undo.half_moves;
+            self.squares_seen_by_white = undo.squares_seen_by_white;
+            self.squares_seen_by_black = undo.squares_seen_by_black;
+            self.hash = undo.hash;
+            Some(mv)
+        }
 
+        // passes the turn, for null move pruning; take it back with unmake_null_move before
+        // any unmake_move. no game reaches the position, so it does not repeat anything before it
+        pub fn make_null_move(& mut self) {
+            self.undo_stack.push(Undo {
+                captured : Piece::empty(),
+                castling : self.castling,
+                en_passant : self.en_passant,
+                half_moves : self.half_moves,
+                squares_seen_by_white : self.squares_seen_by_white,
+                squares_seen_by_black : self.squares_seen_by_black,
+                hash : self.hash,
+                key : self.polyglot_key(),
+                null : true,
+            });
+            self.hash ^= en_passant_key(self.en_passant) ^ turn_key(self.turn);
+            self.en_passant = None;
+            self.half_moves = 0;
+            self.turn = self.turn.opposite();
+            self.hash ^= turn_key(self.turn);
+        }
+
+        pub fn unmake_null_move(& mut self) {
+            if let Some(undo) = self.undo_stack.pop() {
+                self.turn = self.turn.opposite();
+                self.en_passant = undo.en_passant;
+                self.half_moves = undo.half_moves;
+                self.hash = undo.hash;
+            }
+        }
+
+        // the castling rights lost when a piece leaves or lands on the square of their rook
+        fn castling_lost_on(&self, square : (u8, u8)) -> u8 {
+            CASTLING_RIGHTS
+                .iter()
+                .filter(|right| self.castling_rook(**right) == square)
+                .fold(0, |lost, right| lost | right)
+        }
+
+        // source and destination index of the rook taking part in a castling move
+        fn castling_rook_squares(&self, mv : &Move) -> Option<(usize, usize)> {
+            let side = mv.castle?;
+            let color = if mv.source.0 == 1 { Color::White } else { Color::Black };
+            let (rank, file) = self.castling_rook(castling_right(color, side));
+            let rook_file = if side == CastleSide::KingSide { 6 } else { 4 };
+            Some((square_index(rank, file), square_index(rank, rook_file)))
+        }
+    }
+}
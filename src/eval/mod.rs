@@ -0,0 +1,119 @@
+pub mod eval {
+    use crate::{
+        bitboard::bitboard::{squares, PIECE_TYPES},
+        components::chess::{Board, Color, PieceType},
+    };
+
+    pub fn piece_value(piece_type: PieceType) -> i32 {
+        match piece_type {
+            PieceType::Pawn => 100,
+            PieceType::Knight => 320,
+            PieceType::Bishop => 330,
+            PieceType::Rook => 500,
+            PieceType::Queen => 900,
+            PieceType::King | PieceType::Empty => 0,
+        }
+    }
+
+    // piece-square tables of the simplified evaluation function, written as the board is
+    // seen from white's side (a8 first), so white looks up square ^ 56 and black the square
+    #[rustfmt::skip]
+    const PAWN_TABLE: [i32; 64] = [
+         0,  0,  0,  0,  0,  0,  0,  0,
+        50, 50, 50, 50, 50, 50, 50, 50,
+        10, 10, 20, 30, 30, 20, 10, 10,
+         5,  5, 10, 25, 25, 10,  5,  5,
+         0,  0,  0, 20, 20,  0,  0,  0,
+         5, -5,-10,  0,  0,-10, -5,  5,
+         5, 10, 10,-20,-20, 10, 10,  5,
+         0,  0,  0,  0,  0,  0,  0,  0,
+    ];
+
+    #[rustfmt::skip]
+    const KNIGHT_TABLE: [i32; 64] = [
+        -50,-40,-30,-30,-30,-30,-40,-50,
+        -40,-20,  0,  0,  0,  0,-20,-40,
+        -30,  0, 10, 15, 15, 10,  0,-30,
+        -30,  5, 15, 20, 20, 15,  5,-30,
+        -30,  0, 15, 20, 20, 15,  0,-30,
+        -30,  5, 10, 15, 15, 10,  5,-30,
+        -40,-20,  0,  5,  5,  0,-20,-40,
+        -50,-40,-30,-30,-30,-30,-40,-50,
+    ];
+
+    #[rustfmt::skip]
+    const BISHOP_TABLE: [i32; 64] = [
+        -20,-10,-10,-10,-10,-10,-10,-20,
+        -10,  0,  0,  0,  0,  0,  0,-10,
+        -10,  0,  5, 10, 10,  5,  0,-10,
+        -10,  5,  5, 10, 10,  5,  5,-10,
+        -10,  0, 10, 10, 10, 10,  0,-10,
+        -10, 10, 10, 10, 10, 10, 10,-10,
+        -10,  5,  0,  0,  0,  0,  5,-10,
+        -20,-10,-10,-10,-10,-10,-10,-20,
+    ];
+
+    #[rustfmt::skip]
+    const ROOK_TABLE: [i32; 64] = [
+         0,  0,  0,  0,  0,  0,  0,  0,
+         5, 10, 10, 10, 10, 10, 10,  5,
+        -5,  0,  0,  0,  0,  0,  0, -5,
+        -5,  0,  0,  0,  0,  0,  0, -5,
+        -5,  0,  0,  0,  0,  0,  0, -5,
+        -5,  0,  0,  0,  0,  0,  0, -5,
+        -5,  0,  0,  0,  0,  0,  0, -5,
+         0,  0,  0,  5,  5,  0,  0,  0,
+    ];
+
+    #[rustfmt::skip]
+    const QUEEN_TABLE: [i32; 64] = [
+        -20,-10,-10, -5, -5,-10,-10,-20,
+        -10,  0,  0,  0,  0,  0,  0,-10,
+        -10,  0,  5,  5,  5,  5,  0,-10,
+         -5,  0,  5,  5,  5,  5,  0, -5,
+          0,  0,  5,  5,  5,  5,  0, -5,
+        -10,  5,  5,  5,  5,  5,  0,-10,
+        -10,  0,  5,  0,  0,  0,  0,-10,
+        -20,-10,-10, -5, -5,-10,-10,-20,
+    ];
+
+    #[rustfmt::skip]
+    const KING_TABLE: [i32; 64] = [
+        -30,-40,-40,-50,-50,-40,-40,-30,
+        -30,-40,-40,-50,-50,-40,-40,-30,
+        -30,-40,-40,-50,-50,-40,-40,-30,
+        -30,-40,-40,-50,-50,-40,-40,-30,
+        -20,-30,-30,-40,-40,-30,-30,-20,
+        -10,-20,-20,-20,-20,-20,-20,-10,
+         20, 20,  0,  0,  0,  0, 20, 20,
+         20, 30, 10,  0,  0, 10, 30, 20,
+    ];
+
+    fn square_value(piece_type: PieceType, index: usize, color: Color) -> i32 {
+        let index = if color == Color::White { index ^ 56 } else { index };
+        let table = match piece_type {
+            PieceType::Pawn => &PAWN_TABLE,
+            PieceType::Knight => &KNIGHT_TABLE,
+            PieceType::Bishop => &BISHOP_TABLE,
+            PieceType::Rook => &ROOK_TABLE,
+            PieceType::Queen => &QUEEN_TABLE,
+            PieceType::King => &KING_TABLE,
+            PieceType::Empty => return 0,
+        };
+        table[index]
+    }
+
+    // material and piece placement in centipawns, from the side to move's point of view
+    pub fn evaluate(board: &Board) -> i32 {
+        let mut score = 0;
+        for color in [Color::White, Color::Black] {
+            let sign = if color == board.turn { 1 } else { -1 };
+            for piece_type in PIECE_TYPES {
+                for index in squares(board.pieces_of(piece_type, color)) {
+                    score += sign * (piece_value(piece_type) + square_value(piece_type, index, color));
+                }
+            }
+        }
+        score
+    }
+}
@@ -2,9 +2,68 @@
 pub mod fen {
     use std::fmt;
 
-    use crate::components::chess::{Board, Color, Error, Piece, PieceType, Square};
+    use crate::{
+        bitboard::bitboard::{file_of, rank_of},
+        components::chess::{castling_right, Board, Color, Error, Piece, PieceType, Square},
+        moves::moves::{square_index, CastleSide},
+    };
 
-    #[derive(Debug)]
+    const CASTLING_ORDER: [(Color, CastleSide); 4] = [
+        (Color::White, CastleSide::KingSide),
+        (Color::White, CastleSide::QueenSide),
+        (Color::Black, CastleSide::KingSide),
+        (Color::Black, CastleSide::QueenSide),
+    ];
+
+    // how the castling field names the rook of a right: X-FEN writes KQkq whenever the rook
+    // is the outermost one on its side and a file letter otherwise, Shredder-FEN always
+    // writes the file letter
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum CastlingNotation {
+        XFen,
+        Shredder,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum FenError {
+        WrongFieldCount(usize),
+        WrongRankCount(usize),
+        BadRankLength { rank: u8, length: u32 },
+        InvalidPieceChar(char),
+        InvalidTurn(String),
+        InvalidCastling(String),
+        InvalidEnPassant(String),
+        InvalidHalfMoves(String),
+        InvalidFullMoves(String),
+        MissingKing(Color),
+        TooManyKings(Color),
+        PawnOnBackRank(String),
+    }
+
+    impl fmt::Display for FenError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                FenError::WrongFieldCount(count) => write!(f, "expected 6 fields, found {}", count),
+                FenError::WrongRankCount(count) => write!(f, "expected 8 ranks, found {}", count),
+                FenError::BadRankLength { rank, length } => {
+                    write!(f, "rank {} describes {} squares instead of 8", rank, length)
+                }
+                FenError::InvalidPieceChar(c) => write!(f, "invalid piece character: '{}'", c),
+                FenError::InvalidTurn(s) => write!(f, "invalid side to move: '{}'", s),
+                FenError::InvalidCastling(s) => write!(f, "invalid castling rights: '{}'", s),
+                FenError::InvalidEnPassant(s) => write!(f, "invalid en passant square: '{}'", s),
+                FenError::InvalidHalfMoves(s) => write!(f, "invalid half move clock: '{}'", s),
+                FenError::InvalidFullMoves(s) => write!(f, "invalid full move number: '{}'", s),
+                FenError::MissingKing(color) => write!(f, "{} has no king", color),
+                FenError::TooManyKings(color) => write!(f, "{} has more than one king", color),
+                FenError::PawnOnBackRank(square) => write!(f, "pawn on back rank square {}", square),
+            }
+        }
+    }
+
+    impl std::error::Error for FenError {}
+
+    #[derive(Debug, Clone)]
     pub struct Fen {
         pub pieces : String,
         pub turn : char,
@@ -41,6 +100,54 @@ pub mod fen {
         }
     }
 
+    // where the two knights go among the five squares left after the bishops and the queen
+    const KNIGHT_PLACEMENTS: [(usize, usize); 10] = [
+        (0, 1), (0, 2), (0, 3), (0, 4), (1, 2), (1, 3), (1, 4), (2, 3), (2, 4), (3, 4),
+    ];
+
+    // the white back rank of chess960 start position index (0 to 959) as Scharnagl numbers
+    // them, 518 is the classical setup
+    pub fn chess960_back_rank(index : u16) -> Option<[PieceType; 8]> {
+        if index >= 960 {
+            return None;
+        }
+
+        let mut rank = [PieceType::Empty; 8];
+        let mut n = index as usize;
+        rank[2 * (n % 4) + 1] = PieceType::Bishop;
+        n /= 4;
+        rank[2 * (n % 4)] = PieceType::Bishop;
+        n /= 4;
+
+        let empty = |rank : &[PieceType; 8]| -> Vec<usize> {
+            (0..8).filter(|file| rank[*file] == PieceType::Empty).collect()
+        };
+        rank[empty(&rank)[n % 6]] = PieceType::Queen;
+        n /= 6;
+
+        let free = empty(&rank);
+        let (first, second) = KNIGHT_PLACEMENTS[n];
+        rank[free[first]] = PieceType::Knight;
+        rank[free[second]] = PieceType::Knight;
+
+        // the king always stands between the two rooks
+        for (file, piece_type) in empty(&rank).into_iter().zip([PieceType::Rook, PieceType::King, PieceType::Rook]) {
+            rank[file] = piece_type;
+        }
+        Some(rank)
+    }
+
+    pub fn chess960_start_fen(index : u16) -> Option<Fen> {
+        let back_rank : String = chess960_back_rank(index)?
+            .iter()
+            .map(|piece_type| get_char_from_piece(&Piece { piece_type : *piece_type, color : Color::Black }).unwrap())
+            .collect();
+        Some(Fen {
+            pieces : format!("{}/pppppppp/8/8/8/8/PPPPPPPP/{}", back_rank, back_rank.to_ascii_uppercase()),
+            ..start_fen()
+        })
+    }
+
     pub fn get_piece_from_char(c: &char) -> Result<PieceType, Error> {
         match c {
             'r' | 'R' => Ok(PieceType::Rook),
@@ -55,111 +162,285 @@ pub mod fen {
 
 
     pub fn get_char_from_piece(piece: & Piece) -> Result<char , Error> {
-        match ( &piece.piece_type , &piece.color) {
-            (PieceType::Rook, Color::Black) => Ok('R'),
-            (PieceType::Rook, Color::White) => Ok('r'),
-            (PieceType::Pawn, Color::Black) => Ok('P'),
-            (PieceType::Pawn, Color::White) => Ok('p'),
-            (PieceType::Knight, Color::Black) => Ok('N'),
-            (PieceType::Knight, Color::White) => Ok('n'),
-            (PieceType::Bishop, Color::Black) => Ok('B'),
-            (PieceType::Bishop, Color::White) => Ok('b'),
-            (PieceType::King, Color::Black) => Ok('K'),
-            (PieceType::King, Color::White) => Ok('k'),
-            (PieceType::Queen, Color::Black) => Ok('Q'),
-            (PieceType::Queen, Color::White) => Ok('q'),
-            (_, _) => Err(Error::InvalidPieceType(PieceType::Bishop)), //this will never happen
-        }   
-
-    }
-
-    pub fn get_fen_string(fen: &String) -> Vec<&str> {
+        let c = match piece.piece_type {
+            PieceType::Rook => 'r',
+            PieceType::Pawn => 'p',
+            PieceType::Knight => 'n',
+            PieceType::Bishop => 'b',
+            PieceType::King => 'k',
+            PieceType::Queen => 'q',
+            PieceType::Empty => return Err(Error::InvalidPieceType(PieceType::Empty)),
+        };
+        match piece.color {
+            Color::White => Ok(c.to_ascii_uppercase()),
+            Color::Black => Ok(c),
+            Color::NoColor => Err(Error::InvalidPieceType(piece.piece_type)),
+        }
+    }
+
+    pub fn get_fen_string(fen: &str) -> Vec<&str> {
         fen.split('/').collect()
     }
 
-    // PPD = piece placement data
-    pub fn build_ppd(squares : &Vec<Square>) -> String {
-        let mut ppd = String::new();
-        let mut empty_squares = 0;
-        let mut rank = 8;
+    // PPD = piece placement data, written from rank 8 down to rank 1
+    pub fn build_ppd(squares : &[Square]) -> String {
+        let mut ranks : Vec<String> = vec![];
 
-        for (i, square) in squares.iter().enumerate() {
-            if square.piece.piece_type == PieceType::Empty {
-                empty_squares += 1;
-            } else {
+        for rank in squares.chunks(8).rev() {
+            let mut ppd = String::new();
+            let mut empty_squares = 0;
+            for square in rank {
+                if square.piece.piece_type == PieceType::Empty {
+                    empty_squares += 1;
+                    continue;
+                }
                 if empty_squares > 0 {
                     ppd.push_str(&empty_squares.to_string());
                     empty_squares = 0;
                 }
-                let piece_char = get_char_from_piece(&square.piece) .unwrap();
-                ppd.push(piece_char);
+                ppd.push(get_char_from_piece(&square.piece).unwrap());
+            }
+            if empty_squares > 0 {
+                ppd.push_str(&empty_squares.to_string());
             }
+            ranks.push(ppd);
+        }
+        ranks.join("/")
+    }
 
-            if (i + 1) % 8 == 0 {
-                if empty_squares > 0 {
-                    ppd.push_str(&empty_squares.to_string());
-                    empty_squares = 0;
+    // splits the six fields and checks everything but the piece placement
+    pub fn parse_fen(fen : &str) -> Result<Fen, FenError> {
+        let fen_vector : Vec<&str> = fen.split_ascii_whitespace().collect();
+        if fen_vector.len() != 6 {
+            return Err(FenError::WrongFieldCount(fen_vector.len()));
+        }
+
+        let turn = match fen_vector[1] {
+            "w" => 'w',
+            "b" => 'b',
+            turn => return Err(FenError::InvalidTurn(turn.to_string())),
+        };
+
+        let castling = fen_vector[2];
+        let valid_castling = castling == "-"
+            || (!castling.is_empty()
+                && castling.chars().enumerate().all(|(i, c)| {
+                    "KQkqABCDEFGHabcdefgh".contains(c) && !castling[..i].contains(c)
+                }));
+        if !valid_castling {
+            return Err(FenError::InvalidCastling(castling.to_string()));
+        }
+
+        // the en passant square sits behind a pawn the other side just pushed
+        let en_passant = fen_vector[3];
+        let en_passant_rank = if turn == 'w' { 6 } else { 3 };
+        match get_square_from_string(en_passant) {
+            Some((rank, _)) if rank == en_passant_rank => {}
+            None if en_passant == "-" => {}
+            _ => return Err(FenError::InvalidEnPassant(en_passant.to_string())),
+        }
+
+        let half_moves = fen_vector[4]
+            .parse()
+            .map_err(|_| FenError::InvalidHalfMoves(fen_vector[4].to_string()))?;
+        let full_moves = match fen_vector[5].parse() {
+            Ok(full_moves) if full_moves > 0 => full_moves,
+            _ => return Err(FenError::InvalidFullMoves(fen_vector[5].to_string())),
+        };
+
+        Ok(Fen {
+            pieces : fen_vector[0].to_string(),
+            turn,
+            castling : castling.to_string(),
+            en_passant : en_passant.to_string(),
+            half_moves,
+            full_moves,
+        })
+    }
+
+    pub fn build_board_from_fen(fen_string : &str) -> Result<Vec<Square>, FenError> {
+        let mut squares : Vec<Square> = Board::construct_board_vector();
+        let ranks : Vec<&str> = fen_string.split('/').collect();
+        if ranks.len() != 8 {
+            return Err(FenError::WrongRankCount(ranks.len()));
+        }
+
+        for (row, rank_string) in ranks.iter().enumerate() {
+            let rank = 8 - row as u8;
+            let mut length : u32 = 0;
+
+            for c in rank_string.chars() {
+                if let Some(empty) = c.to_digit(10).filter(|empty| (1..=8).contains(empty)) {
+                    length += empty;
+                    continue;
                 }
-                if rank > 1 {
-                    ppd.push('/');
+
+                let piece = Piece{
+                    piece_type : get_piece_from_char(&c).map_err(|_| FenError::InvalidPieceChar(c))?,
+                    color : if c.is_uppercase() {Color::White} else {Color::Black},
+                };
+
+                if length < 8 {
+                    squares[((rank - 1) * 8) as usize + length as usize].piece = piece;
                 }
-                rank -= 1;
+                length += 1;
+            }
+
+            if length != 8 {
+                return Err(FenError::BadRankLength { rank, length });
             }
         }
-        ppd
+
+        for color in [Color::White, Color::Black] {
+            let kings = squares
+                .iter()
+                .filter(|square| square.piece.piece_type == PieceType::King && square.piece.color == color)
+                .count();
+            match kings {
+                0 => return Err(FenError::MissingKing(color)),
+                1 => {}
+                _ => return Err(FenError::TooManyKings(color)),
+            }
+        }
+
+        let pawn_on_back_rank = squares.iter().find(|square| {
+            square.piece.piece_type == PieceType::Pawn && (square.rank == 1 || square.rank == 8)
+        });
+        if let Some(square) = pawn_on_back_rank {
+            return Err(FenError::PawnOnBackRank(get_string_from_square(Some((square.rank, square.file)))));
+        }
+
+        Ok(squares)
     }
 
-    pub fn build_board_from_fen(fen_string : &String) -> Vec<Square> {
-        let mut squares : Vec<Square> = Board::construct_board_vector();
-        let mut index: i8 = 56;
+    fn back_rank(color : Color) -> u8 {
+        if color == Color::White { 1 } else { 8 }
+    }
 
-        for c in fen_string.chars(){
-            if c == '/' {
-                continue;
-            }
-            if index < 0 {
-                break;
+    // file of the rook furthest from the king on one side of it
+    fn outermost_rook(board : &Board, color : Color, side : CastleSide) -> Option<u8> {
+        let rank = back_rank(color);
+        let king_file = file_of(board.king_square(color)?);
+        let rook = Piece { piece_type : PieceType::Rook, color };
+        let is_rook = |file : &u8| board.piece_at(square_index(rank, *file)) == rook;
+        match side {
+            CastleSide::KingSide => (king_file + 1..=8).rev().find(is_rook),
+            CastleSide::QueenSide => (1..king_file).find(is_rook),
+        }
+    }
+
+    // the en passant square has to be empty, with the pawn that was just pushed past it in
+    // front of it and the square that pawn started from empty as well
+    pub fn get_en_passant_from_string(en_passant : &str, board : &Board) -> Result<Option<(u8, u8)>, FenError> {
+        let Some((rank, file)) = get_square_from_string(en_passant) else {
+            return Ok(None);
+        };
+        let pushed = board.turn.opposite();
+        let (pawn_rank, start_rank) = if pushed == Color::White { (rank + 1, rank - 1) } else { (rank - 1, rank + 1) };
+        let pawn = Piece { piece_type : PieceType::Pawn, color : pushed };
+        let empty = |rank : u8| board.piece_at(square_index(rank, file)).piece_type == PieceType::Empty;
+        if board.piece_at(square_index(pawn_rank, file)) != pawn || !empty(rank) || !empty(start_rank) {
+            return Err(FenError::InvalidEnPassant(en_passant.to_string()));
+        }
+        Ok(Some((rank, file)))
+    }
+
+    // the castling rights and the rook files they refer to, KQkq take the outermost rook
+    // and file letters name the rook directly
+    pub fn get_castling_from_string(castling : &str, board : &Board) -> Result<(u8, [u8; 4]), FenError> {
+        let invalid = || FenError::InvalidCastling(castling.to_string());
+        let mut rights = 0;
+        let mut rooks = board.castling_rooks;
+
+        for c in castling.chars().filter(|c| *c != '-') {
+            let color = if c.is_ascii_uppercase() { Color::White } else { Color::Black };
+            let rank = back_rank(color);
+            let king = board.king_square(color).ok_or_else(invalid)?;
+            if rank_of(king) != rank {
+                return Err(invalid());
             }
+            let king_file = file_of(king);
 
-            if c.is_digit(10) {
-                index += c.to_digit(10).unwrap() as i8;
-                if index & 7== 0 {
-                    index -= 16 ;
+            let (side, file) = match c.to_ascii_lowercase() {
+                'k' => (CastleSide::KingSide, outermost_rook(board, color, CastleSide::KingSide)),
+                'q' => (CastleSide::QueenSide, outermost_rook(board, color, CastleSide::QueenSide)),
+                letter => {
+                    let file = letter as u8 - b'a' + 1;
+                    let side = if file > king_file { CastleSide::KingSide } else { CastleSide::QueenSide };
+                    let rook = Piece { piece_type : PieceType::Rook, color };
+                    (side, Some(file).filter(|file| board.piece_at(square_index(rank, *file)) == rook))
                 }
-                continue;
+            };
+
+            let right = castling_right(color, side);
+            if rights & right != 0 {
+                return Err(invalid());
             }
+            rights |= right;
+            rooks[right.trailing_zeros() as usize] = file.ok_or_else(invalid)?;
+        }
+        Ok((rights, rooks))
+    }
 
-            let piece = Piece{
-                piece_type : get_piece_from_char(&c).unwrap(),
-                color : if c.is_uppercase() {Color::White} else {Color::Black},
-            };
+    pub fn get_string_from_castling(board : &Board, notation : CastlingNotation) -> String {
+        let result: String = CASTLING_ORDER
+            .iter()
+            .filter(|(color, side)| board.castling & castling_right(*color, *side) != 0)
+            .map(|(color, side)| {
+                let file = board.castling_rook(castling_right(*color, *side)).1;
+                let c = if notation == CastlingNotation::Shredder
+                    || outermost_rook(board, *color, *side) != Some(file)
+                {
+                    (b'a' + file - 1) as char
+                } else if *side == CastleSide::KingSide {
+                    'k'
+                } else {
+                    'q'
+                };
+                if *color == Color::White { c.to_ascii_uppercase() } else { c }
+            })
+            .collect();
+        if result.is_empty() {
+            "-".to_string()
+        } else {
+            result
+        }
+    }
 
-            squares[index as usize] = Square{
-                piece,
-                rank : (index as u8 >> 3) + 1,
-                file : (index as u8 & 7) + 1,
-            };
-            index += 1;
-            if index & 7== 0 {
-                index -= 16 ;
+    // "e3" -> Some((3, 5)), "-" -> None
+    pub fn get_square_from_string(square : &str) -> Option<(u8, u8)> {
+        let mut iter = square.chars();
+        match (iter.next(), iter.next(), iter.next()) {
+            (Some(file @ 'a'..='h'), Some(rank @ '1'..='8'), None) => {
+                Some((rank as u8 - b'0', file as u8 - b'a' + 1))
             }
+            _ => None,
         }
+    }
 
-        squares
+    pub fn get_string_from_square(square : Option<(u8, u8)>) -> String {
+        match square {
+            Some((rank, file)) => format!("{}{}", (b'a' + file - 1) as char, rank),
+            None => "-".to_string(),
+        }
     }
 
     pub fn build_fen(board : &Board) ->  Fen {
+        build_fen_with(board, CastlingNotation::XFen)
+    }
+
+    pub fn build_fen_with(board : &Board, notation : CastlingNotation) ->  Fen {
         Fen {
-            pieces : self::build_ppd(&board.squares),
-            turn : board.fen.turn,
-            castling : board.fen.castling.clone(),
-            en_passant : board.fen.en_passant.clone(),
-            half_moves : board.fen.half_moves,
-            full_moves : board.fen.full_moves,
+            pieces : self::build_ppd(&board.squares()),
+            turn : if board.turn == Color::White { 'w' } else { 'b' },
+            castling : get_string_from_castling(board, notation),
+            en_passant : get_string_from_square(board.en_passant),
+            half_moves : board.half_moves,
+            full_moves : board.full_moves,
         }
         
     }
 
 
 
-}
\ No newline at end of file
+}
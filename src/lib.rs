@@ -1,6 +1,20 @@
+#![allow(clippy::module_inception)]
 
-
+pub mod attacks;
+pub mod bitboard;
 pub mod fen;
 pub mod components;
+pub mod eval;
 pub mod moves;
-pub mod run;
\ No newline at end of file
+pub mod ordering;
+pub mod outcome;
+pub mod perft;
+pub mod run;
+pub mod san;
+pub mod search;
+pub mod see;
+pub mod time;
+pub mod tt;
+pub mod uci;
+pub mod xboard;
+pub mod zobrist;
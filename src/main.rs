@@ -1,14 +1,61 @@
-use std::process::exit;
+use std::env;
+use std::io::{self, BufRead, Read};
 
 use chess_engine::components::chess;
+use chess_engine::perft::perft::print_divide;
 use chess_engine::run::run;
+use chess_engine::uci::uci::uci_loop;
+use chess_engine::xboard::xboard::xboard_loop;
 
 
 fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    // chess_engine perft <depth> [fen]
+    if args.len() > 2 && args[1] == "perft" {
+        let depth: u32 = match args[2].parse() {
+            Ok(depth) => depth,
+            Err(_) => {
+                println!("perft depth has to be a number: {}", args[2]);
+                return;
+            }
+        };
+        let mut board = match args.get(3).map(|fen| chess::Board::build_from_fen(fen.to_string())) {
+            Some(Ok(board)) => board,
+            Some(Err(e)) => {
+                println!("invalid fen: {}", e);
+                return;
+            }
+            None => chess::Board::build(),
+        };
+        print_divide(&mut board, depth);
+        return;
+    }
+
+    // chess_engine chess960 <index>
+    if args.len() > 2 && args[1] == "chess960" {
+        match args[2].parse().ok().and_then(chess::Board::build_chess960) {
+            Some(mut board) => run(&mut board, io::stdin().lock()),
+            None => println!("chess960 positions are numbered 0 to 959"),
+        }
+        return;
+    }
 
     // let board : chess::Board = chess::Board::build_from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1".to_string());
-    let board : chess::Board = chess::Board::build();
-    run(&board);
+    // a GUI announces the protocol it speaks with its first line, anything else is a move
+    let mut first_line = String::new();
+    if io::stdin().lock().read_line(&mut first_line).is_err() {
+        return;
+    }
+    let input = io::Cursor::new(first_line.clone()).chain(io::stdin().lock());
+    match first_line.trim() {
+        "uci" => return uci_loop(input, io::stdout()),
+        "xboard" => return xboard_loop(input, io::stdout()),
+        _ => {}
+    }
+
+    let mut board : chess::Board = chess::Board::build();
+    run(&mut board, input);
 
 
     println!("i a here");
@@ -1,18 +1,36 @@
 pub mod moves {
 
     use crate::{
-        components::chess::{Board, Color, PieceType, Square},
-        fen::fen::Fen,
+        attacks::attacks::{
+            bishop_attacks, king_attacks, knight_attacks, pawn_attacks, queen_attacks,
+            rook_attacks,
+        },
+        bitboard::bitboard::{bit, color_index, square_of, squares, Bitboard, RANK_1, RANK_8},
+        components::chess::{castling_right, Board, Color, Piece, PieceType, Square},
     };
 
-    #[derive(Debug, Clone, Copy)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum CastleSide {
+        KingSide,
+        QueenSide,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     pub struct Move {
         pub takes: bool,
-        pub castle: Option<bool>,
+        pub castle: Option<CastleSide>,
         pub source: (u8, u8),
         pub destination: (u8, u8),
+        pub promotion: Option<PieceType>,
     }
 
+    pub const PROMOTION_PIECES: [PieceType; 4] = [
+        PieceType::Queen,
+        PieceType::Rook,
+        PieceType::Bishop,
+        PieceType::Knight,
+    ];
+
     pub const KING_MOVES_FILE: [i8; 8] = [0, 0, 1, 1, 1, -1, -1, -1];
     pub const KING_MOVES_RANK: [i8; 8] = [1, -1, 0, 1, -1, 0, 1, -1];
 
@@ -25,407 +43,251 @@ pub mod moves {
     pub const KNIGHT_MOVES_FILE: [i8; 8] = [1, 1, -1, -1, 2, -2, 2, -2];
     pub const KNIGHT_MOVES_RANK: [i8; 8] = [2, -2, 2, -2, 1, 1, -1, -1];
 
-    fn legal_move(rank: i8, file: i8) -> bool {
-        rank >= 1 && rank <= 8 && file >= 1 && file <= 8
+    // ranks and files are 1 based, a1 is index 0 and h8 is index 63
+    pub fn square_index(rank: u8, file: u8) -> usize {
+        (((rank - 1) << 3) + file - 1) as usize
     }
 
-    pub fn create_move(square: &Square, board: &Board, file: i8, rank: i8) -> Option<Move> {
-        let index = (((rank - 1) << 3) + file - 1) as usize;
-        let &destination = &board.squares.get(index).unwrap();
-
-        if destination.piece.color == square.piece.color {
-            return None;
-        }
-
-        if square.piece.piece_type == PieceType::Pawn && destination.piece.color != Color::NoColor {
-            return None;
-        }
-
-        if destination.piece.color != Color::NoColor {
-            return Some(Move {
-                takes: true,
-                castle: None,
-                source: (square.rank, square.file),
-                destination: (rank as u8, file as u8),
-            });
-        }
-
-        Some(Move {
-            takes: false,
+    fn create_move(board: &Board, source: usize, destination: usize) -> Move {
+        Move {
+            takes: board.occupied & bit(destination) != 0,
             castle: None,
-            source: (square.rank, square.file),
-            destination: (rank as u8, file as u8),
-        })
-    }
-
-    pub fn create_move_for_pawn(
-        square: &Square,
-        board: &Board,
-        file: i8,
-        rank: i8,
-        take: bool,
-    ) -> Option<Move> {
-        let index = (((rank - 1) << 3) + file - 1) as usize;
-        let &destination = &board.squares.get(index).unwrap();
-
-        if destination.piece.color == square.piece.color {
-            return None;
-        }
-
-        if take {
-            if destination.piece.color != Color::NoColor {
-                return Some(Move {
-                    takes: true,
-                    castle: None,
-                    source: (square.rank, square.file),
-                    destination: (rank as u8, file as u8),
-                });
-            } else {
-                return None;
-            }
-        } else {
-            if destination.piece.color == Color::NoColor {
-                return Some(Move {
-                    takes: false,
-                    castle: None,
-                    source: (square.rank, square.file),
-                    destination: (rank as u8, file as u8),
-                });
-            } else {
-                return None;
-            }
+            source: square_of(source),
+            destination: square_of(destination),
+            promotion: None,
         }
     }
 
     pub fn get_moves(board: &Board, color: &Color) -> Vec<Move> {
         let mut result: Vec<Move> = vec![];
-        for square in &board.squares {
-            if square.piece.color != *color {
-                continue;
-            }
-            let mut moves = get_move(&square, &board, &board.fen);
-            result.append(&mut moves);
+        for index in squares(board.colors[color_index(color)]) {
+            add_moves_from(board, index, &mut result);
         }
         result
     }
 
-    pub fn get_move(square: &Square, board: &Board, fen: &Fen) -> Vec<Move> {
-        let vector = match square.piece.piece_type {
-            PieceType::King => get_king_moves(&square, &board, fen),
-            PieceType::Bishop => get_bishop_moves(&square, &board),
-            PieceType::Knight => get_knight_moves(&square, &board),
-            PieceType::Queen => get_queen_moves(&square, &board),
-            PieceType::Pawn => get_pawn_moves(&square, &board, fen),
-            PieceType::Rook => get_rook_moves(square, &board),
-            PieceType::Empty => Vec::new(),
-        };
-        vector
+    // only the moves of the side to move that do not leave its own king attacked
+    pub fn get_legal_moves(board: &Board) -> Vec<Move> {
+        let color = board.turn;
+        let mut board = board.clone();
+        get_moves(&board, &color)
+            .into_iter()
+            .filter(|mv| {
+                board.make_move(*mv);
+                let attacked = is_king_attacked(&board, &color);
+                board.unmake_move();
+                !attacked
+            })
+            .collect()
     }
 
-    fn get_king_moves(square: &Square, board: &Board, fen: &Fen) -> Vec<Move> {
-        let mut moves: Vec<Move> = vec![];
-        for index in 0..8 {
-            let new_rank = square.rank as i8 + KING_MOVES_RANK[index];
-            let new_file = square.file as i8 + KING_MOVES_FILE[index];
-
-            if !legal_move(new_rank, new_file) {
-                continue;
-            }
-
-            let the_move: Option<Move> = create_move(square, board, new_file, new_rank);
+    pub fn in_check(board: &Board) -> bool {
+        is_king_attacked(board, &board.turn)
+    }
 
-            if the_move.is_none() {
-                continue;
-            }
+    pub fn is_king_attacked(board: &Board, color: &Color) -> bool {
+        let seen = match color {
+            Color::White => board.squares_seen_by_black,
+            _ => board.squares_seen_by_white,
+        };
 
-            moves.push(the_move.unwrap())
+        match board.king_square(*color) {
+            Some(king) => seen & bit(king) != 0,
+            None => false,
         }
-        moves
     }
 
-    fn get_queen_moves(square: &Square, board: &Board) -> Vec<Move> {
-        let mut rook_like_moves = get_rook_moves(square, board);
-        let mut bishop_like_moves = get_bishop_moves(square, board);
-
-        rook_like_moves.append(&mut bishop_like_moves);
-
-        rook_like_moves
+    pub fn get_squares_seen(board: &Board, color: &Color) -> u64 {
+        let mut seen = 0;
+        for index in squares(board.colors[color_index(color)]) {
+            seen |= attacks_of(&board.piece_at(index), index, board.occupied);
+        }
+        seen
     }
 
-    fn get_knight_moves(square: &Square, board: &Board) -> Vec<Move> {
+    pub fn get_move(square: &Square, board: &Board) -> Vec<Move> {
         let mut moves = vec![];
+        add_moves_from(board, square_index(square.rank, square.file), &mut moves);
+        moves
+    }
 
-        for index in 1..8 {
-            let index = index as usize;
-            let new_file = (square.file as i8) + KNIGHT_MOVES_FILE[index];
-            let new_rank = (square.rank as i8) + KNIGHT_MOVES_RANK[index];
+    fn add_moves_from(board: &Board, index: usize, moves: &mut Vec<Move>) {
+        let piece = board.piece_at(index);
+        let own = match piece.color {
+            Color::NoColor => return,
+            color => board.colors[color_index(&color)],
+        };
 
-            if !legal_move(new_rank, new_file) {
-                continue;
+        let targets = match piece.piece_type {
+            PieceType::Pawn => return add_pawn_moves(board, index, &piece.color, moves),
+            PieceType::King => {
+                add_castling_moves(board, index, &piece.color, moves);
+                king_attacks(index)
             }
+            PieceType::Empty => return,
+            _ => attacks_of(&piece, index, board.occupied),
+        };
 
-            let the_move: Option<Move> = create_move(square, board, new_file, new_rank);
-
-            if the_move.is_none() {
-                continue;
-            }
-            moves.push(the_move.unwrap())
+        for destination in squares(targets & !own) {
+            moves.push(create_move(board, index, destination));
         }
-
-        moves
     }
 
-    fn get_rook_moves(square: &Square, board: &Board) -> Vec<Move> {
-        let mut moves = vec![];
-
-        for direction in 0..4 {
-            let direction = direction as usize;
-            for shift in 1..8 {
-                let new_file = (square.file as i8) + shift * ROOK_MOVES_FILE[direction];
-                let new_rank = (square.rank as i8) + shift * ROOK_MOVES_RANK[direction];
-
-                if !legal_move(new_rank, new_file) {
-                    break;
-                }
-
-                let the_move: Option<Move> = create_move(square, board, new_file, new_rank);
+    // the king ends on the g or c file and the rook next to it on f or d, wherever both
+    // started; every square either of them crosses has to be empty apart from the two
+    // pieces themselves and the king may not be attacked on any square it crosses
+    fn add_castling_moves(board: &Board, index: usize, color: &Color, moves: &mut Vec<Move>) {
+        let (rank, seen) = match color {
+            Color::White => (1, board.squares_seen_by_black),
+            _ => (8, board.squares_seen_by_white),
+        };
 
-                if the_move.is_none() {
-                    break;
-                }
-                moves.push(the_move.unwrap())
-            }
+        let (king_rank, king_file) = square_of(index);
+        if king_rank != rank || seen & bit(index) != 0 {
+            return;
         }
-        moves
-    }
-
-    fn get_bishop_moves(square: &Square, board: &Board) -> Vec<Move> {
-        let mut moves: Vec<Move> = vec![];
 
-        for direction in 0..4 {
-            let direction = direction as usize;
-            for shift in 1..8 {
-                let new_file = (square.file as i8) + shift * BISHOP_MOVES_FILE[direction];
-                let new_rank = (square.rank as i8) + shift * BISHOP_MOVES_RANK[direction];
+        let between = |from: u8, to: u8| -> Bitboard {
+            (from.min(to)..=from.max(to)).fold(0, |squares, file| squares | bit(square_index(rank, file)))
+        };
 
-                if !legal_move(new_rank, new_file) {
-                    break;
-                }
+        for side in [CastleSide::KingSide, CastleSide::QueenSide] {
+            let right = castling_right(*color, side);
+            if board.castling & right == 0 {
+                continue;
+            }
 
-                let the_move: Option<Move> = create_move(square, board, new_file, new_rank);
+            let rook_file = board.castling_rook(right).1;
+            let rook_index = square_index(rank, rook_file);
+            let rook = board.piece_at(rook_index);
+            if rook.piece_type != PieceType::Rook || rook.color != *color {
+                continue;
+            }
 
-                if the_move.is_none() {
-                    break;
-                }
+            let (king_destination, rook_destination) = match side {
+                CastleSide::KingSide => (7, 6),
+                CastleSide::QueenSide => (3, 4),
+            };
 
-                moves.push(the_move.unwrap())
+            let path = (between(king_file, king_destination) | between(rook_file, rook_destination))
+                & !bit(index)
+                & !bit(rook_index);
+            if board.occupied & path != 0 || seen & between(king_file, king_destination) != 0 {
+                continue;
             }
+
+            moves.push(Move {
+                takes: false,
+                castle: Some(side),
+                source: (rank, king_file),
+                destination: (rank, king_destination),
+                promotion: None,
+            });
         }
-        moves
     }
 
-    fn get_pawn_moves(square: &Square, board: &Board, fen: &Fen) -> Vec<Move> {
-        let mut moves = vec![];
-
-        let rank = square.rank;
-        let file = square.file;
-        let direction_based_on_color: i8 = if square.piece.color == Color::White {
-            1
-        } else {
-            -1
+    fn add_pawn_moves(board: &Board, index: usize, color: &Color, moves: &mut Vec<Move>) {
+        let (forward, start_rank): (i8, u8) = match color {
+            Color::White => (8, 2),
+            _ => (-8, 7),
         };
+        let empty = !board.occupied;
 
-        for reps in 1..3 {
-            if legal_move(rank as i8 + direction_based_on_color * reps, file as i8) {
-                let the_move = create_move_for_pawn(
-                    square,
-                    board,
-                    file as i8,
-                    rank as i8 + direction_based_on_color * reps,
-                    false,
-                );
-                if the_move.is_some() {
-                    moves.push(the_move.unwrap());
-                }
-            } else {
-                break;
-            }
+        // the double step is only available from the start rank and only through an empty square
+        let single = index as i8 + forward;
+        if !(0..64).contains(&single) {
+            return;
         }
+        let single = single as usize;
+        if empty & bit(single) != 0 {
+            push_pawn_move(moves, create_move(board, index, single));
 
-        if legal_move(rank as i8 + direction_based_on_color, file as i8 + 1) {
-            let the_move = create_move_for_pawn(
-                square,
-                board,
-                file as i8 + 1,
-                rank as i8 + direction_based_on_color,
-                true,
-            );
-            if the_move.is_some() {
-                moves.push(the_move.unwrap());
+            let double = (single as i8 + forward) as usize;
+            if square_of(index).0 == start_rank && empty & bit(double) != 0 {
+                moves.push(create_move(board, index, double));
             }
         }
 
-        if legal_move(rank as i8 + direction_based_on_color, file as i8 - 1) {
-            let the_move = create_move_for_pawn(
-                square,
-                board,
-                file as i8 - 1,
-                rank as i8 + direction_based_on_color,
-                true,
-            );
-            if the_move.is_some() {
-                moves.push(the_move.unwrap());
-            }
+        let enemies = board.colors[color_index(&color.opposite())];
+        for destination in squares(pawn_attacks(index, color) & enemies) {
+            push_pawn_move(moves, create_move(board, index, destination));
         }
 
-        if fen.en_passant == '-'.to_string() {
-            return moves;
+        if let Some((rank, file)) = board.en_passant {
+            let destination = square_index(rank, file);
+            if pawn_attacks(index, color) & bit(destination) != 0 {
+                moves.push(Move {
+                    takes: true,
+                    ..create_move(board, index, destination)
+                });
+            }
         }
-        let en_passent_file = fen.en_passant.as_bytes()[0] - 96;
-        let en_passent_rank = fen.en_passant.as_bytes()[1] - 48;
+    }
 
-        if rank as i8 + direction_based_on_color == en_passent_rank as i8
-            && file as i8 - 1 == en_passent_file as i8
-        {
-            moves.push(Move {
-                takes: true,
-                castle: None,
-                source: (square.rank, square.file),
-                destination: (en_passent_file, en_passent_rank),
-            })
+    // a pawn reaching the last rank is pushed once for every piece it can promote to
+    fn push_pawn_move(moves: &mut Vec<Move>, the_move: Move) {
+        let destination = square_index(the_move.destination.0, the_move.destination.1);
+        if (RANK_1 | RANK_8) & bit(destination) == 0 {
+            moves.push(the_move);
+            return;
         }
 
-        if rank as i8 + direction_based_on_color == en_passent_rank as i8
-            && file as i8 + 1 == en_passent_file as i8
-        {
+        for piece_type in PROMOTION_PIECES {
             moves.push(Move {
-                takes: true,
-                castle: None,
-                source: (square.rank, square.file),
-                destination: (en_passent_file, en_passent_rank),
-            })
+                promotion: Some(piece_type),
+                ..the_move
+            });
         }
-
-        moves
     }
 
-    pub fn get_protected_squares(square: &Square, squares: &Vec<Square>) -> u64 {
-        let covered = match square.piece.piece_type {
-            PieceType::King => covered_by_king(&square),
-            PieceType::Bishop => covered_by_bishop(&square, &squares),
-            PieceType::Knight => covered_by_knight(&square),
-            PieceType::Queen => covered_by_queen(&square, &squares),
-            PieceType::Pawn => covered_by_pawn(&square),
-            PieceType::Rook => covered_by_rook(square, &squares),
+    // squares attacked by the piece standing on index, sliders stop on the first occupied square
+    pub fn attacks_of(piece: &Piece, index: usize, occupied: Bitboard) -> Bitboard {
+        match piece.piece_type {
+            PieceType::King => king_attacks(index),
+            PieceType::Bishop => bishop_attacks(index, occupied),
+            PieceType::Knight => knight_attacks(index),
+            PieceType::Queen => queen_attacks(index, occupied),
+            PieceType::Pawn => pawn_attacks(index, &piece.color),
+            PieceType::Rook => rook_attacks(index, occupied),
             PieceType::Empty => 0,
-        };
-        covered
+        }
     }
 
-    pub fn covered_by_pawn(square: &Square) -> u64 {
-        let mut covered: u64 = 0;
-        let direction_based_on_color: i8 = if square.piece.color == Color::White {
-            1
-        } else {
-            -1
-        };
-
-        let new_rank = square.rank as i8 + direction_based_on_color;
-
-        if legal_move(new_rank, (square.file + 1) as i8) {
-            let index = ((new_rank << 3) + square.file as i8 + 1) as u64;
-            covered |= 1 << index;
-        }
+    fn occupancy(squares: &[Square]) -> Bitboard {
+        squares
+            .iter()
+            .filter(|square| square.piece.piece_type != PieceType::Empty)
+            .fold(0, |occupied, square| {
+                occupied | bit(square_index(square.rank, square.file))
+            })
+    }
 
-        if legal_move(new_rank, square.file as i8 - 1) {
-            let index = ((new_rank << 3) + square.file as i8 - 1) as u64;
-            covered |= 1 << index;
-        }
+    pub fn get_protected_squares(square: &Square, squares: &[Square]) -> u64 {
+        let index = square_index(square.rank, square.file);
+        attacks_of(&square.piece, index, occupancy(squares))
+    }
 
-        covered
+    pub fn covered_by_pawn(square: &Square) -> u64 {
+        pawn_attacks(square_index(square.rank, square.file), &square.piece.color)
     }
 
-    pub fn covered_by_queen(square: &Square, squares: &Vec<Square>) -> u64 {
-        covered_by_bishop(square, &squares) | covered_by_rook(square, &squares)
+    pub fn covered_by_queen(square: &Square, squares: &[Square]) -> u64 {
+        queen_attacks(square_index(square.rank, square.file), occupancy(squares))
     }
 
-    pub fn covered_by_rook(square: &Square, squares: &Vec<Square>) -> u64 {
-        let mut covered: u64 = 0;
-        for direction in 0..4 {
-            let direction = direction as usize;
-            for shift in 1..8 {
-                let new_file = (square.file as i8) + shift * ROOK_MOVES_FILE[direction];
-                let new_rank = (square.rank as i8) + shift * ROOK_MOVES_RANK[direction];
-                if !legal_move(new_rank, new_file) {
-                    break;
-                }
-
-                let index: u64 = (((new_rank - 1) << 3) + new_file - 1) as u64;
-                covered |= 1 << index;
-
-                if squares.get(index as usize).unwrap().piece.piece_type != PieceType::Empty {
-                    break;
-                }
-            }
-        }
-        covered
+    pub fn covered_by_rook(square: &Square, squares: &[Square]) -> u64 {
+        rook_attacks(square_index(square.rank, square.file), occupancy(squares))
     }
 
     pub fn covered_by_knight(square: &Square) -> u64 {
-        let mut covered: u64 = 0;
-
-        for index in 1..8 {
-            let index = index as usize;
-            let new_file = (square.file as i8) + KNIGHT_MOVES_FILE[index];
-            let new_rank = (square.rank as i8) + KNIGHT_MOVES_RANK[index];
-
-            if !legal_move(new_rank, new_file) {
-                continue;
-            }
-
-            let index: u64 = (((new_rank - 1) << 3) + new_file - 1) as u64;
-            covered |= 1 << index;
-        }
-
-        covered
+        knight_attacks(square_index(square.rank, square.file))
     }
 
-    pub fn covered_by_bishop(square: &Square, squares: &Vec<Square>) -> u64 {
-        let mut covered: u64 = 0;
-
-        for direction in 0..4 {
-            let direction = direction as usize;
-            for shift in 1..8 {
-                let new_file = (square.file as i8) + shift * BISHOP_MOVES_FILE[direction];
-                let new_rank = (square.rank as i8) + shift * BISHOP_MOVES_RANK[direction];
-
-                if !legal_move(new_rank, new_file) {
-                    break;
-                }
-
-                let index: u64 = (((new_rank - 1) << 3) + new_file - 1) as u64;
-                covered |= 1 << index;
-
-                if squares.get(index as usize).unwrap().piece.piece_type != PieceType::Empty {
-                    break;
-                }
-            }
-        }
-        covered
+    pub fn covered_by_bishop(square: &Square, squares: &[Square]) -> u64 {
+        bishop_attacks(square_index(square.rank, square.file), occupancy(squares))
     }
 
     pub fn covered_by_king(square: &Square) -> u64 {
-        let mut covered: u64 = 0;
-        for index in 0..8 {
-            let new_rank = square.rank as i8 + KING_MOVES_RANK[index];
-            let new_file = square.file as i8 + KING_MOVES_FILE[index];
-
-            if !legal_move(new_rank, new_file) {
-                continue;
-            }
-            let index = (((new_rank - 1) << 3) + new_file - 1) as u64;
-            covered |= 1 << index;
-        }
-        covered
+        king_attacks(square_index(square.rank, square.file))
     }
 }
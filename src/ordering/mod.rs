@@ -0,0 +1,153 @@
+pub mod ordering {
+    use std::cmp::Reverse;
+
+    use crate::{
+        bitboard::bitboard::{bit, color_index},
+        components::chess::{Board, Color, PieceType},
+        moves::moves::{square_index, Move},
+        search::search::MAX_PLY,
+    };
+
+    // bands the move scores fall into, a band always beats everything below it
+    const HASH_MOVE: i32 = 4_000_000;
+    const NOISY: i32 = 3_000_000;
+    const FIRST_KILLER: i32 = 2_000_002;
+    const SECOND_KILLER: i32 = 2_000_001;
+    const COUNTERMOVE: i32 = 2_000_000;
+    // history scores stay within plus or minus this
+    const MAX_HISTORY: i32 = 16_384;
+
+    // rank of a piece as victim and as attacker, the king is never taken
+    fn piece_rank(piece_type: PieceType) -> i32 {
+        match piece_type {
+            PieceType::Pawn => 1,
+            PieceType::Knight => 2,
+            PieceType::Bishop => 3,
+            PieceType::Rook => 4,
+            PieceType::Queen => 5,
+            PieceType::King => 6,
+            PieceType::Empty => 0,
+        }
+    }
+
+    fn from_to(mv: &Move) -> usize {
+        square_index(mv.source.0, mv.source.1) * 64 + square_index(mv.destination.0, mv.destination.1)
+    }
+
+    pub fn is_quiet(mv: &Move) -> bool {
+        !mv.takes && mv.promotion.is_none()
+    }
+
+    // most valuable victim first, among those the least valuable attacker first; promotions
+    // count as taking the piece the pawn turns into
+    pub fn mvv_lva(board: &Board, mv: &Move) -> i32 {
+        let destination = square_index(mv.destination.0, mv.destination.1);
+        let victim = if board.occupied & bit(destination) != 0 {
+            board.piece_at(destination).piece_type
+        } else if mv.takes {
+            PieceType::Pawn
+        } else {
+            PieceType::Empty
+        };
+        let attacker = board.piece_at(square_index(mv.source.0, mv.source.1)).piece_type;
+        let promotion = mv.promotion.map_or(0, piece_rank);
+        8 * (piece_rank(victim) + promotion) - piece_rank(attacker)
+    }
+
+    // captures and promotions only, as the quiescence search needs them
+    pub fn order_noisy(board: &Board, moves: &mut [Move]) {
+        moves.sort_by_cached_key(|mv| Reverse(mvv_lva(board, mv)));
+    }
+
+    // what the search learned about quiet moves, kept from one search to the next and
+    // forgotten with clear for a new game
+    pub struct MoveOrdering {
+        // two quiet moves per ply that caused a cutoff there
+        killers: Vec<[Option<Move>; 2]>,
+        // how well a quiet move did, by color, source and destination
+        history: Vec<i32>,
+        // the quiet move that refuted a move, by that move's source and destination
+        countermoves: Vec<Option<Move>>,
+    }
+
+    impl Default for MoveOrdering {
+        fn default() -> MoveOrdering {
+            MoveOrdering::new()
+        }
+    }
+
+    impl MoveOrdering {
+        pub fn new() -> MoveOrdering {
+            MoveOrdering {
+                killers: vec![[None; 2]; MAX_PLY],
+                history: vec![0; 2 * 64 * 64],
+                countermoves: vec![None; 64 * 64],
+            }
+        }
+
+        pub fn clear(&mut self) {
+            *self = MoveOrdering::new();
+        }
+
+        pub fn killers(&self, ply: usize) -> [Option<Move>; 2] {
+            self.killers.get(ply).copied().unwrap_or_default()
+        }
+
+        pub fn history(&self, color: Color, mv: &Move) -> i32 {
+            self.history[color_index(&color) * 64 * 64 + from_to(mv)]
+        }
+
+        // the stored answer to the move that led to the position, a null move has none
+        pub fn countermove(&self, board: &Board) -> Option<Move> {
+            board.last_move().and_then(|previous| self.countermoves[from_to(previous)])
+        }
+
+        // hash move, then captures and promotions by mvv-lva, killers, the countermove and
+        // the remaining quiet moves by history
+        pub fn order(&self, board: &Board, moves: &mut [Move], hash_move: Option<Move>, ply: usize) {
+            let killers = self.killers(ply);
+            let countermove = self.countermove(board);
+            moves.sort_by_cached_key(|mv| {
+                Reverse(if Some(*mv) == hash_move {
+                    HASH_MOVE
+                } else if !is_quiet(mv) {
+                    NOISY + mvv_lva(board, mv)
+                } else if Some(*mv) == killers[0] {
+                    FIRST_KILLER
+                } else if Some(*mv) == killers[1] {
+                    SECOND_KILLER
+                } else if Some(*mv) == countermove {
+                    COUNTERMOVE
+                } else {
+                    self.history(board.turn, mv)
+                })
+            });
+        }
+
+        // a quiet move failed high: it becomes a killer and the countermove to the previous
+        // move, its history goes up and that of the quiet moves tried before it goes down
+        pub fn cutoff(&mut self, board: &Board, mv: Move, ply: usize, depth: u32, tried: &[Move]) {
+            if let Some(killers) = self.killers.get_mut(ply) {
+                if killers[0] != Some(mv) {
+                    killers[1] = killers[0];
+                    killers[0] = Some(mv);
+                }
+            }
+            if let Some(previous) = board.last_move() {
+                self.countermoves[from_to(previous)] = Some(mv);
+            }
+
+            let bonus = (depth * depth).min(MAX_HISTORY as u32) as i32;
+            let color = color_index(&board.turn) * 64 * 64;
+            for quiet in tried.iter().filter(|quiet| **quiet != mv) {
+                update_history(&mut self.history[color + from_to(quiet)], -bonus);
+            }
+            update_history(&mut self.history[color + from_to(&mv)], bonus);
+        }
+    }
+
+    // the closer a score is to the limit, the less a bonus moves it
+    fn update_history(entry: &mut i32, bonus: i32) {
+        *entry += bonus - *entry * bonus.abs() / MAX_HISTORY;
+    }
+}
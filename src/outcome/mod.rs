@@ -0,0 +1,132 @@
+pub mod outcome {
+    use std::fmt;
+
+    use crate::{
+        bitboard::bitboard::{count, Bitboard},
+        components::chess::{Board, Color, PieceType},
+        moves::moves::{get_legal_moves, in_check},
+    };
+
+    // a1 is bit 0 and a dark square
+    const DARK_SQUARES: Bitboard = 0xAA55_AA55_AA55_AA55;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum DrawReason {
+        Stalemate,
+        FiftyMoveRule,
+        ThreefoldRepetition,
+        InsufficientMaterial,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum GameOutcome {
+        Checkmate { winner: Color },
+        Draw(DrawReason),
+    }
+
+    impl GameOutcome {
+        pub fn winner(&self) -> Option<Color> {
+            match self {
+                GameOutcome::Checkmate { winner } => Some(*winner),
+                GameOutcome::Draw(_) => None,
+            }
+        }
+
+        // the result the way PGN and the protocols write it
+        pub fn result(&self) -> &'static str {
+            match self.winner() {
+                Some(Color::White) => "1-0",
+                Some(_) => "0-1",
+                None => "1/2-1/2",
+            }
+        }
+    }
+
+    impl fmt::Display for DrawReason {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                DrawReason::Stalemate => write!(f, "stalemate"),
+                DrawReason::FiftyMoveRule => write!(f, "fifty move rule"),
+                DrawReason::ThreefoldRepetition => write!(f, "threefold repetition"),
+                DrawReason::InsufficientMaterial => write!(f, "insufficient material"),
+            }
+        }
+    }
+
+    impl fmt::Display for GameOutcome {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                GameOutcome::Checkmate { winner } => {
+                    write!(f, "{} {{{} mates}}", self.result(), winner)
+                }
+                GameOutcome::Draw(reason) => write!(f, "{} {{Draw by {}}}", self.result(), reason),
+            }
+        }
+    }
+
+    impl Board {
+        // None while the game goes on
+        pub fn outcome(&self) -> Option<GameOutcome> {
+            if get_legal_moves(self).is_empty() {
+                return Some(if in_check(self) {
+                    GameOutcome::Checkmate {
+                        winner: self.turn.opposite(),
+                    }
+                } else {
+                    GameOutcome::Draw(DrawReason::Stalemate)
+                });
+            }
+
+            if self.is_insufficient_material() {
+                return Some(GameOutcome::Draw(DrawReason::InsufficientMaterial));
+            }
+
+            if self.half_moves >= 100 {
+                return Some(GameOutcome::Draw(DrawReason::FiftyMoveRule));
+            }
+
+            if self.repetitions() >= 3 {
+                return Some(GameOutcome::Draw(DrawReason::ThreefoldRepetition));
+            }
+
+            None
+        }
+
+        // how many times the current position has occurred, itself included; only the
+        // positions since the last capture or pawn move can be equal to it. an en passant
+        // square nobody can take on does not make a position different
+        pub fn repetitions(&self) -> usize {
+            let key = self.polyglot_key();
+            let reversible = (self.half_moves as usize).min(self.undo_stack.len());
+            let earlier = self.undo_stack[self.undo_stack.len() - reversible..]
+                .iter()
+                .filter(|undo| undo.key == key)
+                .count();
+            earlier + 1
+        }
+
+        // neither side can mate: bare kings, a single minor piece, or only bishops all on one color
+        pub fn is_insufficient_material(&self) -> bool {
+            let heavy = self.pieces_of(PieceType::Pawn, Color::White)
+                | self.pieces_of(PieceType::Pawn, Color::Black)
+                | self.pieces_of(PieceType::Rook, Color::White)
+                | self.pieces_of(PieceType::Rook, Color::Black)
+                | self.pieces_of(PieceType::Queen, Color::White)
+                | self.pieces_of(PieceType::Queen, Color::Black);
+            if heavy != 0 {
+                return false;
+            }
+
+            let knights = self.pieces_of(PieceType::Knight, Color::White)
+                | self.pieces_of(PieceType::Knight, Color::Black);
+            let bishops = self.pieces_of(PieceType::Bishop, Color::White)
+                | self.pieces_of(PieceType::Bishop, Color::Black);
+
+            if count(knights | bishops) <= 1 {
+                return true;
+            }
+
+            knights == 0 && (bishops & DARK_SQUARES == 0 || bishops & !DARK_SQUARES == 0)
+        }
+    }
+}
@@ -0,0 +1,55 @@
+pub mod perft {
+    use crate::{
+        components::chess::Board,
+        moves::moves::{get_legal_moves, Move},
+        uci::uci::move_to_uci,
+    };
+
+    impl Board {
+        // number of leaf nodes of the legal move tree, depth 1 is counted straight from the move list
+        pub fn perft(&mut self, depth: u32) -> u64 {
+            if depth == 0 {
+                return 1;
+            }
+
+            let moves = get_legal_moves(self);
+            if depth == 1 {
+                return moves.len() as u64;
+            }
+
+            let mut nodes = 0;
+            for mv in moves {
+                self.make_move(mv);
+                nodes += self.perft(depth - 1);
+                self.unmake_move();
+            }
+            nodes
+        }
+
+        // perft split by root move, the first place to look when a total does not match
+        pub fn perft_divide(&mut self, depth: u32) -> Vec<(Move, u64)> {
+            if depth == 0 {
+                return vec![];
+            }
+
+            let mut result = vec![];
+            for mv in get_legal_moves(self) {
+                self.make_move(mv);
+                result.push((mv, self.perft(depth - 1)));
+                self.unmake_move();
+            }
+            result
+        }
+    }
+
+    pub fn print_divide(board: &mut Board, depth: u32) -> u64 {
+        let mut total = 0;
+        for (mv, nodes) in board.perft_divide(depth) {
+            println!("{}: {}", move_to_uci(board, &mv), nodes);
+            total += nodes;
+        }
+        println!();
+        println!("Nodes searched: {}", total);
+        total
+    }
+}
@@ -0,0 +1,217 @@
+pub mod san {
+    use std::fmt;
+
+    use crate::{
+        components::chess::{Board, Color, Piece, PieceType},
+        fen::fen::{get_char_from_piece, get_piece_from_char, get_square_from_string, get_string_from_square},
+        moves::moves::{get_legal_moves, in_check, square_index, CastleSide, Move},
+    };
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum SanError {
+        // the text is not a move in standard algebraic notation at all
+        InvalidSyntax(String),
+        // well formed, but no legal move in the position fits it
+        IllegalMove(String),
+        // more than one legal move fits it
+        AmbiguousMove(String),
+    }
+
+    impl fmt::Display for SanError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                SanError::InvalidSyntax(san) => write!(f, "not a move: '{}'", san),
+                SanError::IllegalMove(san) => write!(f, "illegal move: '{}'", san),
+                SanError::AmbiguousMove(san) => write!(f, "ambiguous move: '{}'", san),
+            }
+        }
+    }
+
+    impl std::error::Error for SanError {}
+
+    // what the text says about the move, before looking at the position
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    struct SanMove {
+        piece_type: PieceType,
+        file: Option<u8>,
+        rank: Option<u8>,
+        takes: bool,
+        destination: (u8, u8),
+        promotion: Option<PieceType>,
+    }
+
+    // drops check and mate marks and annotations like !, ?, !? and ??
+    fn strip_suffixes(san: &str) -> &str {
+        san.trim_end_matches(['!', '?', '+', '#'])
+    }
+
+    fn castle_side(san: &str) -> Option<CastleSide> {
+        match san {
+            "O-O" | "0-0" | "o-o" => Some(CastleSide::KingSide),
+            "O-O-O" | "0-0-0" | "o-o-o" => Some(CastleSide::QueenSide),
+            _ => None,
+        }
+    }
+
+    fn promotion_piece(c: char) -> Option<PieceType> {
+        match c {
+            'Q' | 'R' | 'B' | 'N' => get_piece_from_char(&c).ok(),
+            _ => None,
+        }
+    }
+
+    fn parse_san_move(san: &str) -> Option<SanMove> {
+        let (piece_type, rest) = match san.chars().next()? {
+            c @ ('K' | 'Q' | 'R' | 'B' | 'N') => (get_piece_from_char(&c).ok()?, &san[1..]),
+            _ => (PieceType::Pawn, san),
+        };
+
+        // a pawn reaching the last rank names its piece, written e8=Q or e8Q
+        let (rest, promotion) = match rest.chars().last().and_then(promotion_piece) {
+            Some(promotion) if piece_type == PieceType::Pawn => {
+                (rest[..rest.len() - 1].trim_end_matches('='), Some(promotion))
+            }
+            _ => (rest, None),
+        };
+
+        if rest.len() < 2 || !rest.is_ascii() {
+            return None;
+        }
+        let (prefix, destination) = rest.split_at(rest.len() - 2);
+        let destination = get_square_from_string(destination)?;
+
+        let (prefix, takes) = match prefix.strip_suffix('x') {
+            Some(prefix) => (prefix, true),
+            None => (prefix, false),
+        };
+
+        // what is left can only be a file, a rank or both, in that order
+        let mut chars = prefix.chars().peekable();
+        let file = chars
+            .next_if(|c| ('a'..='h').contains(c))
+            .map(|c| c as u8 - b'a' + 1);
+        let rank = chars
+            .next_if(|c| ('1'..='8').contains(c))
+            .map(|c| c as u8 - b'0');
+        if chars.next().is_some() {
+            return None;
+        }
+
+        // a pawn capture names the file it comes from and nothing else
+        if piece_type == PieceType::Pawn && ((takes && file.is_none()) || rank.is_some()) {
+            return None;
+        }
+
+        Some(SanMove {
+            piece_type,
+            file,
+            rank,
+            takes,
+            destination,
+            promotion,
+        })
+    }
+
+    // a piece may leave out the x of a capture, but a pawn only captures when it names the
+    // file it comes from, so "d5" never takes on d5
+    fn takes_as_written(parsed: &SanMove, mv: &Move) -> bool {
+        if parsed.piece_type == PieceType::Pawn {
+            mv.takes == parsed.file.is_some()
+        } else {
+            mv.takes || !parsed.takes
+        }
+    }
+
+    // the legal move the text describes
+    pub fn parse_san(board: &Board, san: &str) -> Result<Move, SanError> {
+        let san = san.trim();
+        let stripped = strip_suffixes(san);
+        let legal_moves = get_legal_moves(board);
+
+        let candidates: Vec<Move> = if let Some(side) = castle_side(stripped) {
+            legal_moves.into_iter().filter(|mv| mv.castle == Some(side)).collect()
+        } else {
+            let parsed = parse_san_move(stripped).ok_or_else(|| SanError::InvalidSyntax(san.to_string()))?;
+            legal_moves
+                .into_iter()
+                .filter(|mv| {
+                    mv.castle.is_none()
+                        && board.piece_at(square_index(mv.source.0, mv.source.1)).piece_type == parsed.piece_type
+                        && mv.destination == parsed.destination
+                        && mv.promotion == parsed.promotion
+                        && takes_as_written(&parsed, mv)
+                        && parsed.file.is_none_or(|file| file == mv.source.1)
+                        && parsed.rank.is_none_or(|rank| rank == mv.source.0)
+                })
+                .collect()
+        };
+
+        match candidates[..] {
+            [mv] => Ok(mv),
+            [] => Err(SanError::IllegalMove(san.to_string())),
+            _ => Err(SanError::AmbiguousMove(san.to_string())),
+        }
+    }
+
+    fn piece_letter(piece_type: PieceType) -> char {
+        get_char_from_piece(&Piece { piece_type, color: Color::White }).unwrap()
+    }
+
+    // the move in standard algebraic notation, the move has to be legal in the position
+    pub fn move_to_san(board: &Board, mv: &Move) -> String {
+        let mut san = match mv.castle {
+            Some(CastleSide::KingSide) => "O-O".to_string(),
+            Some(CastleSide::QueenSide) => "O-O-O".to_string(),
+            None => {
+                let piece_type = board.piece_at(square_index(mv.source.0, mv.source.1)).piece_type;
+                let mut san = String::new();
+
+                if piece_type == PieceType::Pawn {
+                    if mv.takes {
+                        san.push((b'a' + mv.source.1 - 1) as char);
+                    }
+                } else {
+                    san.push(piece_letter(piece_type));
+
+                    // only as much of the source square as it takes to tell the pieces apart
+                    let rivals: Vec<Move> = get_legal_moves(board)
+                        .into_iter()
+                        .filter(|other| {
+                            other.castle.is_none()
+                                && other.destination == mv.destination
+                                && other.source != mv.source
+                                && board.piece_at(square_index(other.source.0, other.source.1)).piece_type == piece_type
+                        })
+                        .collect();
+                    let source = get_string_from_square(Some(mv.source));
+                    if rivals.iter().all(|other| other.source.1 != mv.source.1) {
+                        if !rivals.is_empty() {
+                            san.push_str(&source[..1]);
+                        }
+                    } else if rivals.iter().all(|other| other.source.0 != mv.source.0) {
+                        san.push_str(&source[1..]);
+                    } else {
+                        san.push_str(&source);
+                    }
+                }
+
+                if mv.takes {
+                    san.push('x');
+                }
+                san.push_str(&get_string_from_square(Some(mv.destination)));
+                if let Some(promotion) = mv.promotion {
+                    san.push('=');
+                    san.push(piece_letter(promotion));
+                }
+                san
+            }
+        };
+
+        let mut board = board.clone();
+        board.make_move(*mv);
+        if in_check(&board) {
+            san.push(if get_legal_moves(&board).is_empty() { '#' } else { '+' });
+        }
+        san
+    }
+}
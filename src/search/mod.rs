@@ -0,0 +1,581 @@
+pub mod search {
+    use std::{
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        time::{Duration, Instant},
+    };
+
+    use crate::{
+        bitboard::bitboard::{bit, color_index, piece_index},
+        components::chess::{Board, PieceType},
+        eval::eval::{evaluate, piece_value},
+        moves::moves::{get_legal_moves, get_moves, in_check, is_king_attacked, square_index, Move},
+        ordering::ordering::{is_quiet, order_noisy, MoveOrdering},
+        time::time::{Clock, TimeManager},
+        tt::tt::{Bound, TranspositionTable},
+    };
+
+    pub const MAX_PLY: usize = 64;
+    pub const INFINITY: i32 = 32_000;
+    // mated at ply p scores -(MATE - p), so a shorter mate always scores better
+    pub const MATE: i32 = 31_000;
+
+    pub fn is_mate_score(score: i32) -> bool {
+        score.abs() >= MATE - MAX_PLY as i32
+    }
+
+    // full moves until mate as UCI reports it, negative when the side to move is getting mated
+    pub fn mate_in(score: i32) -> Option<i32> {
+        if !is_mate_score(score) {
+            return None;
+        }
+        let plies = MATE - score.abs();
+        Some(if score > 0 { (plies + 1) / 2 } else { -plies / 2 })
+    }
+
+    // no limit at all searches until the stop flag is raised
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    pub struct SearchLimits {
+        pub depth: Option<u32>,
+        pub nodes: Option<u64>,
+        pub movetime: Option<Duration>,
+        // leaves the time to think to a TimeManager
+        pub clock: Option<Clock>,
+    }
+
+    // a capture that can not lift the score to alpha even with this much to spare is skipped
+    const DELTA_MARGIN: i32 = 200;
+    // margins per remaining depth, beyond the deepest one the technique is not used
+    const FUTILITY_MARGINS: [i32; 4] = [0, 150, 300, 450];
+    const REVERSE_FUTILITY_MARGINS: [i32; 7] = [0, 100, 200, 300, 400, 500, 600];
+    const RAZORING_MARGINS: [i32; 3] = [0, 300, 500];
+    const NULL_MOVE_DEPTH: u32 = 3;
+    // moves tried before these in a node are never reduced
+    const LMR_MOVES: usize = 3;
+    const LMR_DEPTH: u32 = 3;
+    const ASPIRATION_DEPTH: u32 = 4;
+    const ASPIRATION_WINDOW: i32 = 50;
+    // a score falling this much from one depth to the next asks for more time
+    const SCORE_DROP: i32 = 30;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct SearchOptions {
+        // also look at quiet checks on the first ply of the quiescence search
+        pub quiescence_checks: bool,
+        pub delta_pruning: bool,
+        pub null_move: bool,
+        pub late_move_reductions: bool,
+        pub futility_pruning: bool,
+        pub reverse_futility_pruning: bool,
+        pub razoring: bool,
+        pub aspiration_windows: bool,
+    }
+
+    impl Default for SearchOptions {
+        fn default() -> SearchOptions {
+            SearchOptions {
+                quiescence_checks: false,
+                delta_pruning: true,
+                null_move: true,
+                late_move_reductions: true,
+                futility_pruning: true,
+                reverse_futility_pruning: true,
+                razoring: true,
+                aspiration_windows: true,
+            }
+        }
+    }
+
+    // how often each selective technique kicked in during one search
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    pub struct SearchStats {
+        pub null_move_cutoffs: u64,
+        pub reductions: u64,
+        // reduced moves that beat alpha and had to be searched again at full depth
+        pub reduction_researches: u64,
+        pub futility_prunes: u64,
+        pub reverse_futility_prunes: u64,
+        pub razoring_cutoffs: u64,
+        pub aspiration_researches: u64,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct SearchResult {
+        pub best_move: Option<Move>,
+        // centipawns from the side to move's point of view, see MATE for mate scores
+        pub score: i32,
+        pub depth: u32,
+        pub nodes: u64,
+        pub pv: Vec<Move>,
+        pub time: Duration,
+        // transposition table fill in permille
+        pub hashfull: u32,
+        pub stats: SearchStats,
+    }
+
+    // state that lives through one search and the tables that outlive it; the stop flag can
+    // be raised from another thread and is polled while searching, it stays raised until the
+    // owner lowers it again
+    pub struct Search {
+        pub options: SearchOptions,
+        tt: TranspositionTable,
+        ordering: MoveOrdering,
+        stop: Arc<AtomicBool>,
+        nodes: u64,
+        stats: SearchStats,
+        // the node about to be searched was reached by a null move
+        after_null: bool,
+        limits: SearchLimits,
+        time: Option<TimeManager>,
+        start: Instant,
+        stopped: bool,
+    }
+
+    impl Default for Search {
+        fn default() -> Search {
+            Search::new()
+        }
+    }
+
+    impl Search {
+        pub fn new() -> Search {
+            Search {
+                options: SearchOptions::default(),
+                tt: TranspositionTable::default(),
+                ordering: MoveOrdering::new(),
+                stop: Arc::new(AtomicBool::new(false)),
+                nodes: 0,
+                stats: SearchStats::default(),
+                after_null: false,
+                limits: SearchLimits::default(),
+                time: None,
+                start: Instant::now(),
+                stopped: false,
+            }
+        }
+
+        pub fn stop_flag(&self) -> Arc<AtomicBool> {
+            Arc::clone(&self.stop)
+        }
+
+        pub fn set_hash_size(&mut self, megabytes: usize) {
+            self.tt.resize(megabytes);
+        }
+
+        // forgets everything learned, for a new game
+        pub fn clear(&mut self) {
+            self.tt.clear();
+            self.ordering.clear();
+        }
+
+        // iterative deepening, report is called after every completed depth; an interrupted
+        // depth is thrown away and the previous one answers
+        pub fn run(
+            &mut self,
+            board: &Board,
+            limits: SearchLimits,
+            mut report: impl FnMut(&SearchResult),
+        ) -> SearchResult {
+            let mut board = board.clone();
+            self.nodes = 0;
+            self.stats = SearchStats::default();
+            self.limits = limits;
+            self.time = limits.clock.map(|clock| TimeManager::new(&clock));
+            self.start = Instant::now();
+            self.stopped = false;
+            self.tt.new_search();
+
+            let first = get_legal_moves(&board).first().copied();
+            let mut result = SearchResult {
+                best_move: first,
+                score: 0,
+                depth: 0,
+                nodes: 0,
+                pv: first.into_iter().collect(),
+                time: Duration::ZERO,
+                hashfull: 0,
+                stats: SearchStats::default(),
+            };
+            if first.is_none() {
+                return result;
+            }
+
+            let max_depth = limits.depth.unwrap_or(MAX_PLY as u32).clamp(1, MAX_PLY as u32 - 1);
+            for depth in 1..=max_depth {
+                let mut pv = vec![];
+                let score = self.aspiration(&mut board, depth, result.score, &mut pv);
+                if self.stopped {
+                    break;
+                }
+
+                // a new best move or a falling score means the position is not settled yet
+                if let Some(time) = &mut self.time {
+                    if depth > 1 && (pv.first() != result.best_move.as_ref() || score < result.score - SCORE_DROP) {
+                        time.extend();
+                    }
+                }
+                result = SearchResult {
+                    best_move: pv.first().copied(),
+                    score,
+                    depth,
+                    nodes: self.nodes,
+                    pv,
+                    time: self.start.elapsed(),
+                    hashfull: self.tt.hashfull(),
+                    stats: self.stats,
+                };
+                report(&result);
+
+                // a mate found within the horizon can not get any shorter
+                if is_mate_score(score) && (MATE - score.abs()) as u32 <= depth {
+                    break;
+                }
+                // the next depth would most likely not finish anyway
+                if self.time.is_some_and(|time| self.start.elapsed() >= time.soft()) {
+                    break;
+                }
+            }
+
+            result.nodes = self.nodes;
+            result.time = self.start.elapsed();
+            result.hashfull = self.tt.hashfull();
+            result.stats = self.stats;
+            result
+        }
+
+        // searches the root in a narrow window around the previous depth's score, widening
+        // it on the side the score fell out of until the score lands inside
+        fn aspiration(&mut self, board: &mut Board, depth: u32, previous: i32, pv: &mut Vec<Move>) -> i32 {
+            if !self.options.aspiration_windows || depth < ASPIRATION_DEPTH || is_mate_score(previous) {
+                return self.negamax(board, depth, 0, -INFINITY, INFINITY, pv);
+            }
+
+            let mut window = ASPIRATION_WINDOW;
+            let (mut alpha, mut beta) = (previous - window, previous + window);
+            loop {
+                let score = self.negamax(board, depth, 0, alpha, beta, pv);
+                if self.stopped || (score > alpha && score < beta) {
+                    return score;
+                }
+
+                self.stats.aspiration_researches += 1;
+                window *= 2;
+                if score <= alpha {
+                    if let Some(time) = &mut self.time {
+                        time.extend();
+                    }
+                    alpha = (score - window).max(-INFINITY);
+                } else {
+                    beta = (score + window).min(INFINITY);
+                }
+            }
+        }
+
+        // counts the node, false once the search has to stop
+        fn visit(&mut self) -> bool {
+            self.nodes += 1;
+            if self.nodes & 255 == 0 && self.should_stop() {
+                self.stopped = true;
+            }
+            !self.stopped
+        }
+
+        fn should_stop(&self) -> bool {
+            self.stop.load(Ordering::Relaxed)
+                || self.limits.nodes.is_some_and(|nodes| self.nodes >= nodes)
+                || self.limits.movetime.is_some_and(|movetime| self.start.elapsed() >= movetime)
+                || self.time.is_some_and(|time| self.start.elapsed() >= time.hard())
+        }
+
+        fn negamax(
+            &mut self,
+            board: &mut Board,
+            depth: u32,
+            ply: usize,
+            mut alpha: i32,
+            mut beta: i32,
+            pv: &mut Vec<Move>,
+        ) -> i32 {
+            pv.clear();
+            let after_null = std::mem::take(&mut self.after_null);
+            if !self.visit() {
+                return 0;
+            }
+
+            if ply > 0 {
+                if board.repetitions() >= 2 || board.is_insufficient_material() {
+                    return 0;
+                }
+
+                // no line from here can beat a mate already found closer to the root
+                alpha = alpha.max(-MATE + ply as i32);
+                beta = beta.min(MATE - ply as i32 - 1);
+                if alpha >= beta {
+                    return alpha;
+                }
+            }
+
+            if ply >= MAX_PLY {
+                return evaluate(board);
+            }
+            if depth == 0 {
+                return self.quiescence(board, ply, alpha, beta, true);
+            }
+
+            // an entry searched at least as deep settles the node unless it is the root,
+            // which has to come up with a move; otherwise its move is tried first
+            let entry = self.tt.probe(board.hash, ply);
+            if let Some(entry) = entry.filter(|entry| ply > 0 && entry.depth >= depth) {
+                match entry.bound {
+                    Bound::Exact => return entry.score,
+                    Bound::Lower if entry.score >= beta => return entry.score,
+                    Bound::Upper if entry.score <= alpha => return entry.score,
+                    _ => {}
+                }
+            }
+
+            let checked = in_check(board);
+            let mut moves = get_legal_moves(board);
+            if moves.is_empty() {
+                return if checked { -MATE + ply as i32 } else { 0 };
+            }
+            if board.half_moves >= 100 {
+                return 0;
+            }
+
+            // nodes off the principal variation are searched with a null window, only there
+            // the static evaluation is trusted to cut the search short
+            let pv_node = beta - alpha > 1;
+            let static_eval = if checked { -INFINITY } else { evaluate(board) };
+            let mut child_pv = vec![];
+            if ply > 0 && !pv_node && !checked {
+                // so far above beta that the opponent will not allow it
+                if self.options.reverse_futility_pruning
+                    && (depth as usize) < REVERSE_FUTILITY_MARGINS.len()
+                    && !is_mate_score(beta)
+                    && static_eval - REVERSE_FUTILITY_MARGINS[depth as usize] >= beta
+                {
+                    self.stats.reverse_futility_prunes += 1;
+                    return static_eval;
+                }
+
+                // so far below alpha that only captures can help, ask the quiescence search
+                if self.options.razoring
+                    && (depth as usize) < RAZORING_MARGINS.len()
+                    && static_eval + RAZORING_MARGINS[depth as usize] < alpha
+                {
+                    let score = self.quiescence(board, ply, alpha, beta, true);
+                    if self.stopped {
+                        return 0;
+                    }
+                    if score <= alpha {
+                        self.stats.razoring_cutoffs += 1;
+                        return score;
+                    }
+                }
+
+                // if passing still holds beta, a real move will too; not twice in a row and
+                // not with pawns alone, where having to move may be what loses
+                if self.options.null_move
+                    && !after_null
+                    && depth >= NULL_MOVE_DEPTH
+                    && static_eval >= beta
+                    && has_pieces(board)
+                {
+                    let reduction = 2 + depth / 4;
+                    board.make_null_move();
+                    self.after_null = true;
+                    let null_depth = depth.saturating_sub(1 + reduction);
+                    let score = -self.negamax(board, null_depth, ply + 1, -beta, -beta + 1, &mut child_pv);
+                    board.unmake_null_move();
+                    if self.stopped {
+                        return 0;
+                    }
+                    if score >= beta {
+                        self.stats.null_move_cutoffs += 1;
+                        // a mate found after passing proves nothing
+                        return if is_mate_score(score) { beta } else { score };
+                    }
+                }
+            }
+
+            // quiet moves can not bring the score up to alpha this close to the horizon
+            let futile = self.options.futility_pruning
+                && ply > 0
+                && !pv_node
+                && !checked
+                && (depth as usize) < FUTILITY_MARGINS.len()
+                && !is_mate_score(alpha)
+                && static_eval + FUTILITY_MARGINS[depth as usize] <= alpha;
+
+            self.ordering.order(board, &mut moves, entry.and_then(|entry| entry.best_move), ply);
+
+            let original_alpha = alpha;
+            let mut best = -INFINITY;
+            let mut best_move = None;
+            let mut quiets = vec![];
+            for (index, mv) in moves.into_iter().enumerate() {
+                board.make_move(mv);
+                let quiet = is_quiet(&mv) && !in_check(board);
+                if futile && quiet && best_move.is_some() {
+                    board.unmake_move();
+                    self.stats.futility_prunes += 1;
+                    continue;
+                }
+
+                // the first move is expected to be best, the others only have to be shown worse
+                // with a null window and get the full one when they are not; late quiet moves
+                // are even searched shallower first
+                let mut score = alpha + 1;
+                if index > 0 {
+                    if self.options.late_move_reductions && depth >= LMR_DEPTH && index >= LMR_MOVES && quiet && !checked {
+                        let reduction = (1 + u32::from(index >= 2 * LMR_MOVES) + u32::from(depth >= 6)).min(depth - 2);
+                        self.stats.reductions += 1;
+                        score = -self.negamax(board, depth - 1 - reduction, ply + 1, -alpha - 1, -alpha, &mut child_pv);
+                        if score > alpha {
+                            self.stats.reduction_researches += 1;
+                        }
+                    }
+                    if score > alpha {
+                        score = -self.negamax(board, depth - 1, ply + 1, -alpha - 1, -alpha, &mut child_pv);
+                    }
+                }
+                if index == 0 || (score > alpha && score < beta) {
+                    score = -self.negamax(board, depth - 1, ply + 1, -beta, -alpha, &mut child_pv);
+                }
+                board.unmake_move();
+                if self.stopped {
+                    return 0;
+                }
+
+                if score > best {
+                    best = score;
+                    best_move = Some(mv);
+                }
+                if score > alpha {
+                    alpha = score;
+                    pv.clear();
+                    pv.push(mv);
+                    pv.extend_from_slice(&child_pv);
+                    if alpha >= beta {
+                        if is_quiet(&mv) {
+                            self.ordering.cutoff(board, mv, ply, depth, &quiets);
+                        }
+                        break;
+                    }
+                }
+                if is_quiet(&mv) {
+                    quiets.push(mv);
+                }
+            }
+
+            let bound = if best >= beta {
+                Bound::Lower
+            } else if best > original_alpha {
+                Bound::Exact
+            } else {
+                Bound::Upper
+            };
+            self.tt.store(board.hash, ply, depth, best, bound, best_move);
+            best
+        }
+
+        // follows captures and promotions until the position is quiet, so the evaluation is
+        // never taken in the middle of an exchange; the side to move may stand pat instead of
+        // capturing, unless it is in check and has to get out of it
+        fn quiescence(&mut self, board: &mut Board, ply: usize, mut alpha: i32, beta: i32, first: bool) -> i32 {
+            if !self.visit() {
+                return 0;
+            }
+            if ply >= MAX_PLY {
+                return evaluate(board);
+            }
+
+            let color = board.turn;
+            if in_check(board) {
+                let moves = get_legal_moves(board);
+                if moves.is_empty() {
+                    return -MATE + ply as i32;
+                }
+
+                let mut best = -INFINITY;
+                for mv in moves {
+                    board.make_move(mv);
+                    let score = -self.quiescence(board, ply + 1, -beta, -alpha, false);
+                    board.unmake_move();
+                    if self.stopped {
+                        return 0;
+                    }
+                    best = best.max(score);
+                    alpha = alpha.max(score);
+                    if alpha >= beta {
+                        break;
+                    }
+                }
+                return best;
+            }
+
+            let stand_pat = evaluate(board);
+            if stand_pat >= beta {
+                return stand_pat;
+            }
+            alpha = alpha.max(stand_pat);
+
+            let checks = first && self.options.quiescence_checks;
+            let mut moves: Vec<Move> = get_moves(board, &color)
+                .into_iter()
+                .filter(|mv| !is_quiet(mv) || (checks && mv.castle.is_none()))
+                .collect();
+            order_noisy(board, &mut moves);
+
+            let mut best = stand_pat;
+            for mv in moves {
+                let noisy = !is_quiet(&mv);
+                if noisy && self.options.delta_pruning && stand_pat + gain(board, &mv) + DELTA_MARGIN < alpha {
+                    continue;
+                }
+
+                board.make_move(mv);
+                if is_king_attacked(board, &color) || (!noisy && !in_check(board)) {
+                    board.unmake_move();
+                    continue;
+                }
+                let score = -self.quiescence(board, ply + 1, -beta, -alpha, false);
+                board.unmake_move();
+                if self.stopped {
+                    return 0;
+                }
+
+                best = best.max(score);
+                alpha = alpha.max(score);
+                if alpha >= beta {
+                    break;
+                }
+            }
+            best
+        }
+    }
+
+    // the side to move has more than pawns and its king
+    fn has_pieces(board: &Board) -> bool {
+        let pawns_and_kings = board.pieces[piece_index(&PieceType::Pawn)] | board.pieces[piece_index(&PieceType::King)];
+        board.colors[color_index(&board.turn)] & !pawns_and_kings != 0
+    }
+
+    // the material a capture or promotion wins at most
+    fn gain(board: &Board, mv: &Move) -> i32 {
+        let destination = square_index(mv.destination.0, mv.destination.1);
+        let captured = if board.occupied & bit(destination) != 0 {
+            piece_value(board.piece_at(destination).piece_type)
+        } else if mv.takes {
+            // en passant
+            piece_value(PieceType::Pawn)
+        } else {
+            0
+        };
+        let promotion = mv.promotion.map_or(0, |piece_type| piece_value(piece_type) - piece_value(PieceType::Pawn));
+        captured + promotion
+    }
+}
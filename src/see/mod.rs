@@ -0,0 +1,107 @@
+pub mod see {
+    use crate::{
+        attacks::attacks::{bishop_attacks, king_attacks, knight_attacks, pawn_attacks, rook_attacks},
+        bitboard::bitboard::{bit, color_index, lsb, piece_index, rank_of, Bitboard},
+        components::chess::{Board, Color, PieceType},
+        eval::eval::piece_value,
+        moves::moves::{square_index, Move},
+    };
+
+    // the order in which pieces join an exchange, the king always comes last
+    const ATTACKER_ORDER: [PieceType; 6] = [
+        PieceType::Pawn,
+        PieceType::Knight,
+        PieceType::Bishop,
+        PieceType::Rook,
+        PieceType::Queen,
+        PieceType::King,
+    ];
+
+    // every piece of either color attacking the square, given what is still on the board
+    fn attackers_to(board: &Board, index: usize, occupied: Bitboard) -> Bitboard {
+        let pieces = |piece_type: PieceType| board.pieces[piece_index(&piece_type)];
+        let queens = pieces(PieceType::Queen);
+        let attackers = (pawn_attacks(index, &Color::Black) & pieces(PieceType::Pawn) & board.colors[0])
+            | (pawn_attacks(index, &Color::White) & pieces(PieceType::Pawn) & board.colors[1])
+            | (knight_attacks(index) & pieces(PieceType::Knight))
+            | (king_attacks(index) & pieces(PieceType::King))
+            | (bishop_attacks(index, occupied) & (pieces(PieceType::Bishop) | queens))
+            | (rook_attacks(index, occupied) & (pieces(PieceType::Rook) | queens));
+        attackers & occupied
+    }
+
+    // the least valuable piece among the attackers of one color
+    fn least_valuable(board: &Board, attackers: Bitboard) -> Option<(usize, PieceType)> {
+        ATTACKER_ORDER.iter().find_map(|piece_type| {
+            let candidates = attackers & board.pieces[piece_index(piece_type)];
+            (candidates != 0).then(|| (lsb(candidates), *piece_type))
+        })
+    }
+
+    // material the side making the move ends up with once both sides have taken back on the
+    // destination square as long as it pays off, each time with their least valuable piece;
+    // sliders lined up behind a capturer join in once it has moved. pins are not looked at
+    // and castling never wins or loses anything
+    pub fn see(board: &Board, mv: &Move) -> i32 {
+        if mv.castle.is_some() {
+            return 0;
+        }
+
+        let source = square_index(mv.source.0, mv.source.1);
+        let destination = square_index(mv.destination.0, mv.destination.1);
+        let mut occupied = board.occupied & !bit(source);
+        let mut gains = [0; 32];
+        gains[0] = if board.occupied & bit(destination) != 0 {
+            piece_value(board.piece_at(destination).piece_type)
+        } else if mv.takes {
+            // en passant, the captured pawn sits behind the destination
+            let captured = if mv.destination.0 > mv.source.0 { destination - 8 } else { destination + 8 };
+            occupied &= !bit(captured);
+            piece_value(PieceType::Pawn)
+        } else {
+            0
+        };
+
+        // value of the piece now standing on the destination, which is next to be taken
+        let mut on_square = piece_value(board.piece_at(source).piece_type);
+        if let Some(promotion) = mv.promotion {
+            gains[0] += piece_value(promotion) - piece_value(PieceType::Pawn);
+            on_square = piece_value(promotion);
+        }
+
+        let mut color = board.piece_at(source).color.opposite();
+        let mut depth = 0;
+        loop {
+            let attackers = attackers_to(board, destination, occupied);
+            let Some((index, piece_type)) = least_valuable(board, attackers & board.colors[color_index(&color)])
+            else {
+                break;
+            };
+            // the king may only take back when nothing recaptures
+            if piece_type == PieceType::King && attackers & board.colors[color_index(&color.opposite())] != 0 {
+                break;
+            }
+
+            depth += 1;
+            gains[depth] = on_square - gains[depth - 1];
+            on_square = piece_value(piece_type);
+            if piece_type == PieceType::Pawn && matches!(rank_of(destination), 1 | 8) {
+                gains[depth] += piece_value(PieceType::Queen) - piece_value(PieceType::Pawn);
+                on_square = piece_value(PieceType::Queen);
+            }
+
+            if depth == gains.len() - 1 {
+                break;
+            }
+            occupied &= !bit(index);
+            color = color.opposite();
+        }
+
+        // each side either takes back or stops, whichever leaves it better off
+        while depth > 0 {
+            gains[depth - 1] = -(-gains[depth - 1]).max(gains[depth]);
+            depth -= 1;
+        }
+        gains[0]
+    }
+}
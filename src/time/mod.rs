@@ -0,0 +1,61 @@
+pub mod time {
+    use std::time::Duration;
+
+    // kept back on every move for the GUI, the pipe and the operating system
+    pub const MOVE_OVERHEAD: Duration = Duration::from_millis(50);
+    // how many more moves a game is expected to last when the time control does not say
+    const DEFAULT_MOVES_TO_GO: u32 = 30;
+    // the most a single move may take, in shares of the time per move
+    const MAX_SHARES: u32 = 4;
+
+    // the clock of the side to move
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    pub struct Clock {
+        pub time: Duration,
+        pub increment: Duration,
+        // moves until the next time control tops the clock up, None for sudden death
+        pub moves_to_go: Option<u32>,
+    }
+
+    // how long to think on one move: no new depth is started past the soft deadline, the
+    // search is stopped wherever it is at the hard one
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct TimeManager {
+        // an even split of the clock over the moves left, plus the increment
+        share: Duration,
+        soft: Duration,
+        hard: Duration,
+    }
+
+    impl TimeManager {
+        pub fn new(clock: &Clock) -> TimeManager {
+            let available = clock.time.saturating_sub(MOVE_OVERHEAD);
+            let moves_to_go = clock.moves_to_go.unwrap_or(DEFAULT_MOVES_TO_GO).max(1);
+            let share = available / moves_to_go + clock.increment;
+
+            // the last move before the control may use up the clock, any other keeps half of
+            // it for the moves after
+            let limit = if moves_to_go == 1 { available } else { available / 2 };
+            let hard = (share * MAX_SHARES).min(limit);
+            TimeManager {
+                share,
+                soft: share.min(hard / 2),
+                hard,
+            }
+        }
+
+        pub fn soft(&self) -> Duration {
+            self.soft
+        }
+
+        pub fn hard(&self) -> Duration {
+            self.hard
+        }
+
+        // the position needs a closer look, because the best move keeps changing or the score
+        // fell; every call allows half a share more, never past the hard deadline
+        pub fn extend(&mut self) {
+            self.soft = (self.soft + self.share / 2).min(self.hard);
+        }
+    }
+}
@@ -0,0 +1,141 @@
+pub mod tt {
+    use std::mem::size_of;
+
+    use crate::{
+        moves::moves::Move,
+        search::search::is_mate_score,
+    };
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Bound {
+        Exact,
+        // the score is at least this much, the search failed high
+        Lower,
+        // the score is at most this much, no move raised alpha
+        Upper,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Entry {
+        pub key: u64,
+        pub best_move: Option<Move>,
+        pub score: i32,
+        pub depth: u32,
+        pub bound: Bound,
+        generation: u8,
+    }
+
+    // two entries share a bucket, a new position pushes out whichever of them is worth less
+    const BUCKET_SIZE: usize = 2;
+
+    pub const DEFAULT_HASH_MB: usize = 16;
+
+    pub struct TranspositionTable {
+        entries: Vec<Option<Entry>>,
+        // bumped by every search so entries from earlier searches get replaced first
+        generation: u8,
+    }
+
+    impl Default for TranspositionTable {
+        fn default() -> TranspositionTable {
+            TranspositionTable::new(DEFAULT_HASH_MB)
+        }
+    }
+
+    impl TranspositionTable {
+        pub fn new(megabytes: usize) -> TranspositionTable {
+            let buckets = (megabytes.max(1) << 20) / (size_of::<Option<Entry>>() * BUCKET_SIZE);
+            TranspositionTable {
+                entries: vec![None; buckets.max(1) * BUCKET_SIZE],
+                generation: 0,
+            }
+        }
+
+        pub fn resize(&mut self, megabytes: usize) {
+            *self = TranspositionTable::new(megabytes);
+        }
+
+        pub fn clear(&mut self) {
+            self.entries.fill(None);
+            self.generation = 0;
+        }
+
+        pub fn new_search(&mut self) {
+            self.generation = self.generation.wrapping_add(1);
+        }
+
+        fn bucket(&self, key: u64) -> usize {
+            let buckets = (self.entries.len() / BUCKET_SIZE) as u128;
+            ((key as u128 * buckets) >> 64) as usize * BUCKET_SIZE
+        }
+
+        // the score comes back relative to the probing ply, see store
+        pub fn probe(&self, key: u64, ply: usize) -> Option<Entry> {
+            let bucket = self.bucket(key);
+            self.entries[bucket..bucket + BUCKET_SIZE]
+                .iter()
+                .flatten()
+                .find(|entry| entry.key == key)
+                .map(|entry| Entry {
+                    score: score_from_tt(entry.score, ply),
+                    ..*entry
+                })
+        }
+
+        // mate scores are stored as the distance from this position rather than from the root,
+        // so the entry stays right when the position is reached at another ply
+        pub fn store(&mut self, key: u64, ply: usize, depth: u32, score: i32, bound: Bound, best_move: Option<Move>) {
+            let bucket = self.bucket(key);
+            let generation = self.generation;
+            let slots = &mut self.entries[bucket..bucket + BUCKET_SIZE];
+
+            let worth = |entry: &Option<Entry>| match entry {
+                None => i32::MIN,
+                Some(entry) if entry.key == key => i32::MIN,
+                Some(entry) => entry.depth as i32 - 4 * generation.wrapping_sub(entry.generation) as i32,
+            };
+            let slot = (0..BUCKET_SIZE).min_by_key(|slot| worth(&slots[*slot])).unwrap();
+
+            // a shallower search of the same position keeps the move it does not know
+            let best_move = match slots[slot] {
+                Some(entry) if entry.key == key => best_move.or(entry.best_move),
+                _ => best_move,
+            };
+            slots[slot] = Some(Entry {
+                key,
+                best_move,
+                score: score_to_tt(score, ply),
+                depth,
+                bound,
+                generation,
+            });
+        }
+
+        // how full the table is in permille, sampled over the first thousand entries
+        pub fn hashfull(&self) -> u32 {
+            let sample = &self.entries[..self.entries.len().min(1000)];
+            let used = sample
+                .iter()
+                .flatten()
+                .filter(|entry| entry.generation == self.generation)
+                .count();
+            (used * 1000 / sample.len()) as u32
+        }
+    }
+
+    fn score_to_tt(score: i32, ply: usize) -> i32 {
+        match score {
+            score if is_mate_score(score) && score > 0 => score + ply as i32,
+            score if is_mate_score(score) => score - ply as i32,
+            score => score,
+        }
+    }
+
+    fn score_from_tt(score: i32, ply: usize) -> i32 {
+        match score {
+            score if is_mate_score(score) && score > 0 => score - ply as i32,
+            score if is_mate_score(score) => score + ply as i32,
+            score => score,
+        }
+    }
+}
@@ -0,0 +1,354 @@
+pub mod uci {
+    use std::{
+        fmt,
+        io::{BufRead, Write},
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc, Mutex,
+        },
+        thread::{self, JoinHandle},
+        time::Duration,
+    };
+
+    use crate::{
+        components::chess::{castling_right, Board, Color, PieceType},
+        fen::fen::{get_square_from_string, get_string_from_square},
+        moves::moves::{get_legal_moves, Move},
+        search::search::{mate_in, Search, SearchLimits, SearchResult},
+        time::time::Clock,
+        tt::tt::DEFAULT_HASH_MB,
+    };
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum UciMoveError {
+        // not two squares followed by an optional promotion letter
+        InvalidSyntax(String),
+        // well formed, but not a legal move in the position
+        IllegalMove(String),
+    }
+
+    impl fmt::Display for UciMoveError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                UciMoveError::InvalidSyntax(text) => write!(f, "not a move: '{}'", text),
+                UciMoveError::IllegalMove(text) => write!(f, "illegal move: '{}'", text),
+            }
+        }
+    }
+
+    impl std::error::Error for UciMoveError {}
+
+    // where a castling move is written to go: the king's own destination in normal chess,
+    // the square of its rook in chess960 where g1 or c1 can also be a plain king move, and
+    // whenever the king already stands on its destination and would not seem to move at all
+    fn castling_destination(board: &Board, mv: &Move, chess960: bool) -> (u8, u8) {
+        match mv.castle {
+            Some(side) if chess960 || mv.destination == mv.source => {
+                board.castling_rook(castling_right(board.turn, side))
+            }
+            _ => mv.destination,
+        }
+    }
+
+    // long algebraic notation as UCI uses it: e2e4, e7e8q, e1g1 (e1h1 in chess960)
+    pub fn move_to_uci(board: &Board, mv: &Move) -> String {
+        let promotion = match mv.promotion {
+            Some(PieceType::Queen) => "q",
+            Some(PieceType::Rook) => "r",
+            Some(PieceType::Bishop) => "b",
+            Some(PieceType::Knight) => "n",
+            _ => "",
+        };
+        format!(
+            "{}{}{}",
+            get_string_from_square(Some(mv.source)),
+            get_string_from_square(Some(castling_destination(board, mv, board.chess960))),
+            promotion
+        )
+    }
+
+    // the legal move the text describes, castling may be given as the king move or, as in
+    // chess960, as the king taking its own rook
+    pub fn parse_uci_move(board: &Board, text: &str) -> Result<Move, UciMoveError> {
+        let invalid = || UciMoveError::InvalidSyntax(text.to_string());
+        let text = text.trim();
+        if !text.is_ascii() || !(4..=5).contains(&text.len()) {
+            return Err(invalid());
+        }
+
+        let source = get_square_from_string(&text[..2]).ok_or_else(invalid)?;
+        let destination = get_square_from_string(&text[2..4]).ok_or_else(invalid)?;
+        let promotion = match &text[4..] {
+            "" => None,
+            "q" => Some(PieceType::Queen),
+            "r" => Some(PieceType::Rook),
+            "b" => Some(PieceType::Bishop),
+            "n" => Some(PieceType::Knight),
+            _ => return Err(invalid()),
+        };
+
+        get_legal_moves(board)
+            .into_iter()
+            .find(|mv| {
+                let written = match mv.castle {
+                    Some(_) if !board.chess960 => {
+                        destination == mv.destination || destination == castling_destination(board, mv, true)
+                    }
+                    _ => destination == castling_destination(board, mv, board.chess960),
+                };
+                mv.source == source && written && mv.promotion == promotion
+            })
+            .ok_or_else(|| UciMoveError::IllegalMove(text.to_string()))
+    }
+
+    // the limits of a go command, times in milliseconds
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    pub struct Go {
+        pub wtime: Option<u64>,
+        pub btime: Option<u64>,
+        pub winc: Option<u64>,
+        pub binc: Option<u64>,
+        pub movestogo: Option<u32>,
+        pub depth: Option<u32>,
+        pub nodes: Option<u64>,
+        pub movetime: Option<u64>,
+        pub infinite: bool,
+    }
+
+    // unknown tokens and values that do not parse are skipped, some GUIs send negative times
+    pub fn parse_go(args: &[&str]) -> Go {
+        let mut go = Go::default();
+        for (i, token) in args.iter().enumerate() {
+            let number = || {
+                args.get(i + 1)
+                    .and_then(|value| value.parse::<i64>().ok())
+                    .map(|value| value.max(0) as u64)
+            };
+            match *token {
+                "wtime" => go.wtime = number(),
+                "btime" => go.btime = number(),
+                "winc" => go.winc = number(),
+                "binc" => go.binc = number(),
+                "movetime" => go.movetime = number(),
+                "nodes" => go.nodes = number(),
+                "movestogo" => go.movestogo = number().map(|value| value as u32),
+                "depth" => go.depth = number().map(|value| value as u32),
+                "infinite" => go.infinite = true,
+                _ => {}
+            }
+        }
+        go
+    }
+
+    // a board from "startpos|fen <fields> [moves <moves>]", None if the position or a move is bad
+    pub fn parse_position(args: &[&str], chess960: bool) -> Option<Board> {
+        let moves_at = args.iter().position(|token| *token == "moves").unwrap_or(args.len());
+        let mut board = match args.first() {
+            Some(&"startpos") => Board::build(),
+            Some(&"fen") => Board::build_from_fen(args[1..moves_at].join(" ")).ok()?,
+            _ => return None,
+        };
+        board.chess960 = chess960;
+
+        for text in args.iter().skip(moves_at + 1) {
+            let mv = parse_uci_move(&board, text).ok()?;
+            board.make_move(mv);
+        }
+        Some(board)
+    }
+
+    // what the search gets out of a go command, the clock of the side to move is left to
+    // the time manager
+    pub fn search_limits(go: &Go, turn: Color) -> SearchLimits {
+        if go.infinite {
+            return SearchLimits::default();
+        }
+
+        let (time, increment) = match turn {
+            Color::White => (go.wtime, go.winc),
+            _ => (go.btime, go.binc),
+        };
+        let clock = time.map(|time| Clock {
+            time: Duration::from_millis(time),
+            increment: Duration::from_millis(increment.unwrap_or(0)),
+            moves_to_go: go.movestogo,
+        });
+        SearchLimits {
+            depth: go.depth,
+            nodes: go.nodes,
+            movetime: go.movetime.map(Duration::from_millis),
+            clock,
+        }
+    }
+
+    // "cp 35" or "mate -3"
+    pub fn format_score(score: i32) -> String {
+        match mate_in(score) {
+            Some(moves) => format!("mate {}", moves),
+            None => format!("cp {}", score),
+        }
+    }
+
+    pub fn info_line(board: &Board, result: &SearchResult) -> String {
+        let millis = result.time.as_millis() as u64;
+        let pv: Vec<String> = pv_to_uci(board, &result.pv);
+        format!(
+            "info depth {} score {} nodes {} nps {} hashfull {} time {} pv {}",
+            result.depth,
+            format_score(result.score),
+            result.nodes,
+            result.nodes * 1000 / millis.max(1),
+            result.hashfull,
+            millis,
+            pv.join(" ")
+        )
+    }
+
+    // the principal variation in coordinate notation, played out on a copy of the board
+    pub fn pv_to_uci(board: &Board, pv: &[Move]) -> Vec<String> {
+        let mut board = board.clone();
+        pv.iter()
+            .map(|mv| {
+                let text = move_to_uci(&board, mv);
+                board.make_move(*mv);
+                text
+            })
+            .collect()
+    }
+
+    // where the protocol loops and their search threads write, one line at a time
+    pub type Output<W> = Arc<Mutex<W>>;
+
+    pub fn send<W: Write>(output: &Output<W>, line: &str) {
+        let mut output = output.lock().unwrap();
+        let _ = writeln!(output, "{}", line);
+        let _ = output.flush();
+    }
+
+    pub struct UciEngine<W: Write + Send + 'static> {
+        board: Board,
+        chess960: bool,
+        output: Output<W>,
+        context: Arc<Mutex<Search>>,
+        stop: Arc<AtomicBool>,
+        search: Option<JoinHandle<()>>,
+    }
+
+    impl<W: Write + Send + 'static> UciEngine<W> {
+        pub fn new(output: W) -> UciEngine<W> {
+            let context = Search::new();
+            UciEngine {
+                board: Board::build(),
+                chess960: false,
+                output: Arc::new(Mutex::new(output)),
+                stop: context.stop_flag(),
+                context: Arc::new(Mutex::new(context)),
+                search: None,
+            }
+        }
+
+        // handles one line from the GUI, false once it asked to quit
+        pub fn handle(&mut self, line: &str) -> bool {
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            let Some((command, args)) = tokens.split_first() else {
+                return true;
+            };
+
+            match *command {
+                "uci" => {
+                    send(&self.output, &format!("id name {} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")));
+                    send(&self.output, "id author the chess_engine developers");
+                    send(&self.output, &format!("option name Hash type spin default {} min 1 max 4096", DEFAULT_HASH_MB));
+                    send(&self.output, "option name Clear Hash type button");
+                    send(&self.output, "option name UCI_Chess960 type check default false");
+                    send(&self.output, "uciok");
+                }
+                "isready" => send(&self.output, "readyok"),
+                "ucinewgame" => {
+                    self.stop_search();
+                    self.board = Board::build();
+                    self.context.lock().unwrap().clear();
+                }
+                "setoption" => self.set_option(args),
+                "position" => {
+                    self.stop_search();
+                    match parse_position(args, self.chess960) {
+                        Some(board) => self.board = board,
+                        None => send(&self.output, &format!("info string invalid position: {}", args.join(" "))),
+                    }
+                }
+                "go" => self.go(parse_go(args)),
+                "stop" => self.stop_search(),
+                "quit" => {
+                    self.stop_search();
+                    return false;
+                }
+                _ => send(&self.output, &format!("info string unknown command: {}", line.trim())),
+            }
+            true
+        }
+
+        // setoption name <name> [value <value>], names may contain spaces
+        fn set_option(&mut self, args: &[&str]) {
+            let value_at = args.iter().position(|token| *token == "value").unwrap_or(args.len());
+            let name = args.get(1..value_at).unwrap_or_default().join(" ");
+            let value = args.get(value_at + 1..).unwrap_or_default().join(" ");
+
+            self.stop_search();
+            match name.to_lowercase().as_str() {
+                "hash" => match value.parse::<usize>() {
+                    Ok(megabytes) => self.context.lock().unwrap().set_hash_size(megabytes.clamp(1, 4096)),
+                    Err(_) => send(&self.output, &format!("info string invalid hash size: {}", value)),
+                },
+                "clear hash" => self.context.lock().unwrap().clear(),
+                "uci_chess960" => {
+                    self.chess960 = value == "true";
+                    self.board.chess960 = self.chess960;
+                }
+                _ => send(&self.output, &format!("info string unknown option: {}", name)),
+            }
+        }
+
+        fn go(&mut self, go: Go) {
+            self.stop_search();
+            let board = self.board.clone();
+            let context = Arc::clone(&self.context);
+            let stop = Arc::clone(&self.stop);
+            let output = Arc::clone(&self.output);
+
+            self.search = Some(thread::spawn(move || {
+                let limits = search_limits(&go, board.turn);
+                let result = context.lock().unwrap().run(&board, limits, |result| {
+                    send(&output, &info_line(&board, result));
+                });
+
+                // an infinite search only answers once it is told to stop
+                while go.infinite && !stop.load(Ordering::Relaxed) {
+                    thread::sleep(Duration::from_millis(1));
+                }
+                let best = result.best_move.map_or("0000".to_string(), |mv| move_to_uci(&board, &mv));
+                send(&output, &format!("bestmove {}", best));
+            }));
+        }
+
+        // tells a running search to stop and waits for its bestmove
+        pub fn stop_search(&mut self) {
+            if let Some(search) = self.search.take() {
+                self.stop.store(true, Ordering::Relaxed);
+                let _ = search.join();
+                self.stop.store(false, Ordering::Relaxed);
+            }
+        }
+    }
+
+    // reads commands until quit or the end of the input
+    pub fn uci_loop(input: impl BufRead, output: impl Write + Send + 'static) {
+        let mut engine = UciEngine::new(output);
+        for line in input.lines().map_while(Result::ok) {
+            if !engine.handle(&line) {
+                return;
+            }
+        }
+        engine.stop_search();
+    }
+}
@@ -0,0 +1,321 @@
+pub mod xboard {
+    use std::{
+        io::{BufRead, Write},
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc, Mutex,
+        },
+        thread::{self, JoinHandle},
+    };
+
+    use crate::{
+        components::chess::{Board, Color},
+        moves::moves::{CastleSide, Move},
+        san::san::parse_san,
+        search::search::{is_mate_score, Search, SearchResult, MATE},
+        uci::uci::{move_to_uci, parse_uci_move, pv_to_uci, search_limits, send, Go, Output},
+    };
+
+    // the clock as level, st and sd set it up, times in milliseconds
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    pub struct TimeControl {
+        pub moves_per_session: Option<u32>,
+        pub base: u64,
+        pub increment: u64,
+        pub per_move: Option<u64>,
+        pub depth: Option<u32>,
+    }
+
+    // "level 40 5 0", "level 0 2:30 1": moves per session, base minutes[:seconds], increment seconds
+    pub fn parse_level(args: &[&str]) -> Option<TimeControl> {
+        let [moves, base, increment] = args else {
+            return None;
+        };
+
+        let base = match base.split_once(':') {
+            Some((minutes, seconds)) => minutes.parse::<u64>().ok()? * 60 + seconds.parse::<u64>().ok()?,
+            None => base.parse::<u64>().ok()? * 60,
+        };
+        let moves: u32 = moves.parse().ok()?;
+        Some(TimeControl {
+            moves_per_session: Some(moves).filter(|moves| *moves > 0),
+            base: base * 1000,
+            increment: (increment.parse::<f64>().ok()? * 1000.0) as u64,
+            per_move: None,
+            depth: None,
+        })
+    }
+
+    fn first_number(args: &[&str]) -> Option<u64> {
+        args.first()?.parse().ok()
+    }
+
+    pub struct XboardEngine<W: Write + Send + 'static> {
+        board: Board,
+        // xboard names the variant again after every new
+        chess960: bool,
+        // the side the engine plays, None in force mode
+        engine_color: Option<Color>,
+        post: bool,
+        time_control: TimeControl,
+        engine_time: Option<u64>,
+        opponent_time: Option<u64>,
+        output: Output<W>,
+        context: Arc<Mutex<Search>>,
+        stop: Arc<AtomicBool>,
+        // set together with stop when the interface no longer wants the move
+        abandon: Arc<AtomicBool>,
+        // the engine thinking on its own thread, it answers with the move it played
+        search: Option<JoinHandle<Option<Move>>>,
+    }
+
+    impl<W: Write + Send + 'static> XboardEngine<W> {
+        pub fn new(output: W) -> XboardEngine<W> {
+            let context = Search::new();
+            XboardEngine {
+                board: Board::build(),
+                chess960: false,
+                engine_color: Some(Color::Black),
+                post: false,
+                // what xboard itself starts with: 40 moves in 5 minutes
+                time_control: TimeControl {
+                    moves_per_session: Some(40),
+                    base: 300_000,
+                    ..TimeControl::default()
+                },
+                engine_time: None,
+                opponent_time: None,
+                output: Arc::new(Mutex::new(output)),
+                stop: context.stop_flag(),
+                context: Arc::new(Mutex::new(context)),
+                abandon: Arc::new(AtomicBool::new(false)),
+                search: None,
+            }
+        }
+
+        fn send(&self, line: &str) {
+            send(&self.output, line);
+        }
+
+        // handles one line from the interface, false once it asked to quit
+        pub fn handle(&mut self, line: &str) -> bool {
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            let Some((command, args)) = tokens.split_first() else {
+                return true;
+            };
+
+            // these cut the engine's thinking short, anything else waits for its move
+            match *command {
+                "?" => self.stop_search(true),
+                "force" | "result" | "new" | "quit" => self.stop_search(false),
+                _ => self.finish_search(),
+            }
+
+            match *command {
+                "xboard" => {}
+                "protover" => self.send(&format!(
+                    "feature myname=\"{} {}\" setboard=1 usermove=1 ping=1 memory=1 playother=1 colors=0 sigint=0 sigterm=0 reuse=1 analyze=0 variants=\"normal,fischerandom\" done=1",
+                    env!("CARGO_PKG_NAME"),
+                    env!("CARGO_PKG_VERSION")
+                )),
+                "new" => {
+                    self.chess960 = false;
+                    self.board = Board::build();
+                    self.context.lock().unwrap().clear();
+                    self.engine_color = Some(Color::Black);
+                    self.time_control.depth = None;
+                }
+                "variant" => {
+                    self.chess960 = args.first() == Some(&"fischerandom");
+                    self.board.chess960 = self.chess960;
+                }
+                "setboard" => match Board::build_from_fen(args.join(" ")) {
+                    Ok(board) => {
+                        self.board = board;
+                        self.board.chess960 = self.chess960;
+                    }
+                    Err(e) => self.send(&format!("tellusererror Illegal position: {}", e)),
+                },
+                "usermove" => self.user_move(args.first().copied().unwrap_or_default()),
+                "go" => {
+                    self.engine_color = Some(self.board.turn);
+                    self.play();
+                }
+                "playother" => self.engine_color = Some(self.board.turn.opposite()),
+                "force" | "result" => self.engine_color = None,
+                "level" => match parse_level(args) {
+                    Some(time_control) => {
+                        self.time_control = TimeControl { depth: self.time_control.depth, ..time_control }
+                    }
+                    None => self.send(&format!("Error (bad level): {}", line.trim())),
+                },
+                "st" => self.time_control.per_move = first_number(args).map(|seconds| seconds * 1000),
+                "sd" => self.time_control.depth = first_number(args).map(|depth| depth as u32),
+                "time" => self.engine_time = first_number(args).map(|centiseconds| centiseconds * 10),
+                "otim" => self.opponent_time = first_number(args).map(|centiseconds| centiseconds * 10),
+                "undo" => {
+                    self.board.unmake_move();
+                }
+                "remove" => {
+                    self.board.unmake_move();
+                    self.board.unmake_move();
+                }
+                "memory" => match first_number(args) {
+                    Some(megabytes) => self.context.lock().unwrap().set_hash_size(megabytes.clamp(1, 4096) as usize),
+                    None => self.send(&format!("Error (bad memory): {}", line.trim())),
+                },
+                "post" => self.post = true,
+                "nopost" => self.post = false,
+                "ping" => self.send(&format!("pong {}", args.first().copied().unwrap_or_default())),
+                "quit" => return false,
+                "accepted" | "rejected" | "random" | "hard" | "easy" | "computer" | "name" | "rating" | "ics" | "white" | "black" | "?" => {}
+                _ => self.send(&format!("Error (unknown command): {}", command)),
+            }
+            true
+        }
+
+        // coordinate notation first, SAN as sent for chess960 castling and by some interfaces
+        fn user_move(&mut self, text: &str) {
+            let mv = parse_uci_move(&self.board, text).or_else(|_| parse_san(&self.board, text));
+            match mv {
+                Ok(mv) => {
+                    self.board.make_move(mv);
+                    if !self.game_over() && self.engine_color == Some(self.board.turn) {
+                        self.play();
+                    }
+                }
+                Err(_) => self.send(&format!("Illegal move: {}", text)),
+            }
+        }
+
+        // announces the result once the game is decided
+        fn game_over(&self) -> bool {
+            announce_outcome(&self.output, &self.board)
+        }
+
+        // the limits for a search of the side to move
+        pub fn limits(&self) -> Go {
+            let time_control = &self.time_control;
+            let moves_to_go = time_control
+                .moves_per_session
+                .map(|moves| moves - (self.board.full_moves - 1) % moves);
+            // before the first time command the clocks are full
+            let engine_time = self.engine_time.or(Some(time_control.base));
+            let opponent_time = self.opponent_time.or(Some(time_control.base));
+            let (wtime, btime) = match self.board.turn {
+                Color::White => (engine_time, opponent_time),
+                _ => (opponent_time, engine_time),
+            };
+            Go {
+                wtime,
+                btime,
+                winc: Some(time_control.increment),
+                binc: Some(time_control.increment),
+                movestogo: moves_to_go,
+                depth: time_control.depth,
+                movetime: time_control.per_move,
+                ..Go::default()
+            }
+        }
+
+        // starts thinking on a thread, the move is sent from there once the search ends
+        fn play(&mut self) {
+            if self.game_over() {
+                return;
+            }
+
+            let board = self.board.clone();
+            let limits = search_limits(&self.limits(), board.turn);
+            let post = self.post;
+            let context = Arc::clone(&self.context);
+            let abandon = Arc::clone(&self.abandon);
+            let output = Arc::clone(&self.output);
+
+            self.search = Some(thread::spawn(move || {
+                let result = context.lock().unwrap().run(&board, limits, |result| {
+                    if post {
+                        send(&output, &thinking_line(&board, result));
+                    }
+                });
+                let mv = result.best_move.filter(|_| !abandon.load(Ordering::Relaxed))?;
+
+                send(&output, &format!("move {}", xboard_move(&board, &mv)));
+                let mut board = board;
+                board.make_move(mv);
+                announce_outcome(&output, &board);
+                Some(mv)
+            }));
+        }
+
+        // waits for the engine's move, if it is thinking, and plays it on the board
+        pub fn finish_search(&mut self) {
+            if let Some(search) = self.search.take() {
+                if let Ok(Some(mv)) = search.join() {
+                    self.board.make_move(mv);
+                }
+            }
+        }
+
+        // makes the engine answer at once with the best move so far, or not at all
+        pub fn stop_search(&mut self, play: bool) {
+            if self.search.is_some() {
+                self.abandon.store(!play, Ordering::Relaxed);
+                self.stop.store(true, Ordering::Relaxed);
+                self.finish_search();
+                self.stop.store(false, Ordering::Relaxed);
+                self.abandon.store(false, Ordering::Relaxed);
+            }
+        }
+    }
+
+    fn announce_outcome<W: Write>(output: &Output<W>, board: &Board) -> bool {
+        match board.outcome() {
+            Some(outcome) => {
+                send(output, &outcome.to_string());
+                true
+            }
+            None => false,
+        }
+    }
+
+    // ply, score in centipawns, time in centiseconds, nodes and the principal variation
+    fn thinking_line(board: &Board, result: &SearchResult) -> String {
+        format!(
+            "{} {} {} {} {}",
+            result.depth,
+            xboard_score(result.score),
+            result.time.as_millis() / 10,
+            result.nodes,
+            pv_to_uci(board, &result.pv).join(" ")
+        )
+    }
+
+    // xboard wants chess960 castling spelled out
+    fn xboard_move(board: &Board, mv: &Move) -> String {
+        match mv.castle {
+            Some(CastleSide::KingSide) if board.chess960 => "O-O".to_string(),
+            Some(CastleSide::QueenSide) if board.chess960 => "O-O-O".to_string(),
+            _ => move_to_uci(board, mv),
+        }
+    }
+
+    // xboard writes mate in n moves as 100000 + n
+    fn xboard_score(score: i32) -> i32 {
+        if !is_mate_score(score) {
+            return score;
+        }
+        let moves = (MATE - score.abs() + 1) / 2;
+        if score > 0 { 100_000 + moves } else { -100_000 - moves }
+    }
+
+    // reads commands until quit or the end of the input
+    pub fn xboard_loop(input: impl BufRead, output: impl Write + Send + 'static) {
+        let mut engine = XboardEngine::new(output);
+        for line in input.lines().map_while(Result::ok) {
+            if !engine.handle(&line) {
+                return;
+            }
+        }
+        engine.finish_search();
+    }
+}
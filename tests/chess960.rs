@@ -0,0 +1,80 @@
+use std::collections::HashSet;
+
+use chess_engine::{
+    components::chess::{Board, PieceType},
+    fen::fen::chess960_back_rank,
+    moves::moves::{get_legal_moves, CastleSide},
+};
+
+#[test]
+fn start_positions_by_index() {
+    assert_eq!(
+        Board::build_chess960(518).unwrap().to_fen(),
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"
+    );
+    assert_eq!(
+        Board::build_chess960(0).unwrap().to_fen(),
+        "bbqnnrkr/pppppppp/8/8/8/8/PPPPPPPP/BBQNNRKR w KQkq - 0 1"
+    );
+    assert!(Board::build_chess960(960).is_none());
+}
+
+#[test]
+fn every_start_position_is_distinct_and_legal() {
+    let mut seen = HashSet::new();
+    for index in 0..960 {
+        let rank = chess960_back_rank(index).unwrap();
+        let files = |piece_type| -> Vec<usize> { (0..8).filter(|file| rank[*file] == piece_type).collect() };
+
+        let bishops = files(PieceType::Bishop);
+        let rooks = files(PieceType::Rook);
+        let king = files(PieceType::King);
+        assert_ne!(bishops[0] % 2, bishops[1] % 2, "{}", index);
+        assert!(rooks[0] < king[0] && king[0] < rooks[1], "{}", index);
+        assert!(seen.insert(Board::build_chess960(index).unwrap().to_fen()), "{}", index);
+    }
+}
+
+// reference counts from the chess960 perft collection on the chess programming wiki
+#[test]
+fn chess960_perft() {
+    let cases = [
+        ("bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9", [21, 528, 12189]),
+        ("2nnrbkr/p1qppppp/8/1ppb4/6PP/3PP3/PPP2P2/BQNNRBKR w HEhe - 1 9", [21, 807, 18002]),
+        ("b1q1rrkb/pppppppp/3nn3/8/P7/1PPP4/4PPPP/BQNNRKRB w GE - 1 9", [20, 479, 10471]),
+    ];
+
+    for (fen, counts) in cases {
+        let mut board = Board::build_from_fen(fen.to_string()).unwrap();
+        for (depth, nodes) in counts.iter().enumerate() {
+            assert_eq!(board.perft(depth as u32 + 1), *nodes, "{} depth {}", fen, depth + 1);
+        }
+    }
+}
+
+#[test]
+fn castling_where_the_king_lands_on_its_rook() {
+    // king on f1 and rook on g1: the king ends on g1, the rook on f1
+    let fen = "4k3/8/8/8/8/8/8/5KR1 w K - 0 1";
+    let mut board = Board::build_from_fen(fen.to_string()).unwrap();
+    let castle = get_legal_moves(&board)
+        .into_iter()
+        .find(|mv| mv.castle == Some(CastleSide::KingSide))
+        .unwrap();
+    board.make_move(castle);
+    assert_eq!(board.to_fen(), "4k3/8/8/8/8/8/8/5RK1 b - - 1 1");
+    board.unmake_move();
+    assert_eq!(board.to_fen(), fen);
+
+    // king already on g1: only the rook moves
+    let fen = "4k3/8/8/8/8/8/8/6KR w K - 0 1";
+    let mut board = Board::build_from_fen(fen.to_string()).unwrap();
+    let castle = get_legal_moves(&board)
+        .into_iter()
+        .find(|mv| mv.castle == Some(CastleSide::KingSide))
+        .unwrap();
+    board.make_move(castle);
+    assert_eq!(board.to_fen(), "4k3/8/8/8/8/8/8/5RK1 b - - 1 1");
+    board.unmake_move();
+    assert_eq!(board.to_fen(), fen);
+}
@@ -0,0 +1,26 @@
+use std::{
+    io::{self, Write},
+    sync::{Arc, Mutex},
+};
+
+// output a protocol loop can own while the test keeps reading it
+#[derive(Clone, Default)]
+pub struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuffer {
+    pub fn lines(&self) -> Vec<String> {
+        let output = String::from_utf8(self.0.lock().unwrap().clone()).unwrap();
+        output.lines().map(str::to_string).collect()
+    }
+}
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
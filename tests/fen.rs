@@ -0,0 +1,161 @@
+use chess_engine::{
+    components::chess::{Board, Color, WHITE_KING_SIDE},
+    fen::fen::FenError,
+    moves::moves::get_legal_moves,
+};
+
+fn error(fen: &str) -> FenError {
+    Board::build_from_fen(fen.to_string()).err().unwrap()
+}
+
+#[test]
+fn valid_fens_parse() {
+    for fen in [
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        "rnbqkbnr/pppp1ppp/8/8/3Pp3/8/PPP1PPPP/RNBQKBNR b KQkq d3 0 2",
+        "8/8/8/8/8/8/8/K6k w - - 99 120",
+    ] {
+        assert!(Board::build_from_fen(fen.to_string()).is_ok(), "{}", fen);
+    }
+}
+
+#[test]
+fn malformed_fens_are_reported() {
+    let cases = [
+        ("8/8/8/8/8/8/8/K6k w - -", FenError::WrongFieldCount(4)),
+        ("8/8/8/8/8/8/K6k w - - 0 1", FenError::WrongRankCount(7)),
+        ("8/8/8/8/8/8/7/K6k w - - 0 1", FenError::BadRankLength { rank: 2, length: 7 }),
+        ("8/8/8/8/8/8/9/K6k w - - 0 1", FenError::InvalidPieceChar('9')),
+        ("8/8/8/8/8/8/8/K5pkp w - - 0 1", FenError::BadRankLength { rank: 1, length: 9 }),
+        ("8/8/8/8/8/8/8/K5xk w - - 0 1", FenError::InvalidPieceChar('x')),
+        ("8/8/8/8/8/8/8/K6k x - - 0 1", FenError::InvalidTurn("x".to_string())),
+        ("8/8/8/8/8/8/8/K6k w KK - 0 1", FenError::InvalidCastling("KK".to_string())),
+        ("8/8/8/8/8/8/8/K6k w KX - 0 1", FenError::InvalidCastling("KX".to_string())),
+        ("8/8/8/8/8/8/8/K6k w - e3 0 1", FenError::InvalidEnPassant("e3".to_string())),
+        ("8/8/8/8/8/8/8/K6k w - i6 0 1", FenError::InvalidEnPassant("i6".to_string())),
+        // the square behind has to hold the pawn that was pushed, not a knight or nothing
+        ("4k3/8/8/3Pn3/8/8/8/4K3 w - e6 0 1", FenError::InvalidEnPassant("e6".to_string())),
+        ("4k3/8/8/3P4/8/8/8/4K3 w - e6 0 1", FenError::InvalidEnPassant("e6".to_string())),
+        ("4k3/8/4n3/3Pp3/8/8/8/4K3 w - e6 0 1", FenError::InvalidEnPassant("e6".to_string())),
+        ("4k3/4n3/8/3Pp3/8/8/8/4K3 w - e6 0 1", FenError::InvalidEnPassant("e6".to_string())),
+        ("4k3/8/8/8/4P3/8/4N3/4K3 b - e3 0 1", FenError::InvalidEnPassant("e3".to_string())),
+        ("8/8/8/8/8/8/8/K6k w - - x 1", FenError::InvalidHalfMoves("x".to_string())),
+        ("8/8/8/8/8/8/8/K6k w - - 0 0", FenError::InvalidFullMoves("0".to_string())),
+        ("8/8/8/8/8/8/8/7k w - - 0 1", FenError::MissingKing(Color::White)),
+        ("8/8/8/8/8/8/8/K6K w - - 0 1", FenError::TooManyKings(Color::White)),
+        ("8/8/8/8/8/8/8/Kk5k w - - 0 1", FenError::TooManyKings(Color::Black)),
+        ("4p3/8/8/8/8/8/8/K6k w - - 0 1", FenError::PawnOnBackRank("e8".to_string())),
+    ];
+
+    for (fen, expected) in cases {
+        assert_eq!(error(fen), expected, "{}", fen);
+    }
+}
+
+const START_POSITIONS: [&str; 4] = [
+    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+];
+
+// xorshift, so every run walks the same games
+fn next_random(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+}
+
+fn assert_round_trip(board: &Board) {
+    let fen = board.to_fen();
+    let parsed = Board::build_from_fen(fen.clone()).unwrap();
+    assert_eq!(parsed.to_fen(), fen);
+    assert_eq!(parsed.squares(), board.squares(), "{}", fen);
+    assert_eq!(parsed.hash, board.hash, "{}", fen);
+}
+
+#[test]
+fn known_fens_are_written_back_unchanged() {
+    for fen in START_POSITIONS {
+        assert_eq!(Board::build_from_fen(fen.to_string()).unwrap().to_fen(), fen);
+    }
+    assert_eq!(Board::build().to_fen(), START_POSITIONS[0]);
+}
+
+#[test]
+fn to_fen_follows_the_position() {
+    let mut board = Board::build();
+    let e4 = get_legal_moves(&board)
+        .into_iter()
+        .find(|mv| mv.source == (2, 5) && mv.destination == (4, 5))
+        .unwrap();
+    board.make_move(e4);
+    assert_eq!(
+        board.to_fen(),
+        "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1"
+    );
+}
+
+#[test]
+fn random_games_round_trip() {
+    let mut state = 0x9E37_79B9_7F4A_7C15;
+    for fen in START_POSITIONS {
+        for _ in 0..25 {
+            let mut board = Board::build_from_fen(fen.to_string()).unwrap();
+            for _ in 0..80 {
+                assert_round_trip(&board);
+                let moves = get_legal_moves(&board);
+                if moves.is_empty() {
+                    break;
+                }
+                board.make_move(moves[next_random(&mut state) as usize % moves.len()]);
+            }
+        }
+    }
+}
+
+#[test]
+fn shredder_and_x_fen_castling() {
+    let start = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w HAha - 0 1";
+    let board = Board::build_from_fen(start.to_string()).unwrap();
+    assert_eq!(board.castling_rooks, [8, 1, 8, 1]);
+    assert_eq!(board.to_fen(), START_POSITIONS[0]);
+    assert_eq!(board.to_shredder_fen(), start);
+
+    // the inner rook needs its file, the outermost one keeps the plain letter
+    let fen = "r1k1r2r/8/8/8/8/8/8/R3K1RR w GAkq - 0 1";
+    let board = Board::build_from_fen(fen.to_string()).unwrap();
+    assert_eq!(board.castling_rooks, [7, 1, 8, 1]);
+    assert_eq!(board.to_fen(), "r1k1r2r/8/8/8/8/8/8/R3K1RR w GQkq - 0 1");
+    assert_eq!(board.to_shredder_fen(), "r1k1r2r/8/8/8/8/8/8/R3K1RR w GAha - 0 1");
+    assert_round_trip(&board);
+}
+
+#[test]
+fn castling_follows_the_tracked_rook() {
+    let fen = "4k3/8/8/8/8/8/8/4K1RR w G - 0 1";
+    let mut board = Board::build_from_fen(fen.to_string()).unwrap();
+    let moves = get_legal_moves(&board);
+
+    let outer = moves.iter().find(|mv| mv.source == (1, 8) && mv.destination == (2, 8)).unwrap();
+    board.make_move(*outer);
+    assert_eq!(board.castling, WHITE_KING_SIDE);
+    board.unmake_move();
+
+    let inner = moves.iter().find(|mv| mv.source == (1, 7) && mv.destination == (2, 7)).unwrap();
+    board.make_move(*inner);
+    assert_eq!(board.castling, 0);
+}
+
+#[test]
+fn castling_rights_need_their_rook() {
+    for (fen, castling) in [
+        ("4k3/8/8/8/8/8/8/4K3 w K - 0 1", "K"),
+        ("4k3/8/8/8/8/8/8/R3K3 w B - 0 1", "B"),
+        ("4k3/8/8/8/8/8/4K3/R6R w HA - 0 1", "HA"),
+        ("4k3/8/8/8/8/8/8/R3K2R w AQ - 0 1", "AQ"),
+    ] {
+        assert_eq!(error(fen), FenError::InvalidCastling(castling.to_string()), "{}", fen);
+    }
+}
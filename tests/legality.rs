@@ -0,0 +1,51 @@
+use chess_engine::{components::chess::Board, moves::moves::get_legal_moves};
+
+fn square_name((rank, file): (u8, u8)) -> String {
+    format!("{}{}", (b'a' + file - 1) as char, rank)
+}
+
+// the legal moves as sorted "e2e4" strings
+fn legal_moves(fen: &str) -> Vec<String> {
+    let board = Board::build_from_fen(fen.to_string()).unwrap();
+    let mut moves: Vec<String> = get_legal_moves(&board)
+        .iter()
+        .map(|mv| square_name(mv.source) + &square_name(mv.destination))
+        .collect();
+    moves.sort();
+    moves
+}
+
+#[test]
+fn check_has_to_be_answered() {
+    // the rook checks along the e-file, the queen can only block on e2
+    assert_eq!(legal_moves("k3r3/8/8/8/8/8/8/R2QK3 w - - 0 1"), ["d1e2", "e1d2", "e1f1", "e1f2"]);
+}
+
+#[test]
+fn double_check_leaves_only_king_moves() {
+    assert_eq!(legal_moves("4r2k/8/8/8/1b6/8/8/3QK3 w - - 0 1"), ["e1f1", "e1f2"]);
+}
+
+#[test]
+fn pinned_pieces_stay_on_the_pin() {
+    // the rook may slide along the file and take the pinner, the knight may not move at all
+    let rook: Vec<String> = legal_moves("4k3/4r3/8/8/8/8/4R3/4K3 w - - 0 1")
+        .into_iter()
+        .filter(|mv| mv.starts_with("e2"))
+        .collect();
+    assert_eq!(rook, ["e2e3", "e2e4", "e2e5", "e2e6", "e2e7"]);
+    assert!(!legal_moves("4k3/4r3/8/8/8/8/4N3/4K3 w - - 0 1").iter().any(|mv| mv.starts_with("e2")));
+}
+
+#[test]
+fn king_does_not_walk_into_attacks() {
+    // d2 and f2 are covered by the pawn, the first rank by the rook
+    assert_eq!(legal_moves("4k3/8/8/8/8/4p3/8/r2K4 w - - 0 1"), ["d1c2", "d1e2"]);
+}
+
+#[test]
+fn en_passant_discovering_check_is_illegal() {
+    // taking removes both pawns from the fifth rank and opens it for the rook
+    assert!(!legal_moves("8/8/8/KPp4r/8/8/8/4k3 w - c6 0 1").contains(&"b5c6".to_string()));
+    assert!(legal_moves("8/8/8/KPp5/8/8/8/4k3 w - c6 0 1").contains(&"b5c6".to_string()));
+}
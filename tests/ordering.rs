@@ -0,0 +1,96 @@
+use chess_engine::{
+    components::chess::{Board, Color},
+    moves::moves::{get_legal_moves, Move},
+    ordering::ordering::{mvv_lva, order_noisy, MoveOrdering},
+    uci::uci::{move_to_uci, parse_uci_move},
+};
+
+// a pawn and a rook to take, the pawn three ways
+const FEN: &str = "4k3/8/8/3p1r2/4P3/2N5/8/3QK3 w - - 0 1";
+
+fn board() -> Board {
+    Board::build_from_fen(FEN.to_string()).unwrap()
+}
+
+fn uci(board: &Board, mv: &str) -> Move {
+    parse_uci_move(board, mv).unwrap()
+}
+
+fn ordered(ordering: &MoveOrdering, board: &Board, hash_move: Option<Move>, ply: usize) -> Vec<String> {
+    let mut moves = get_legal_moves(board);
+    ordering.order(board, &mut moves, hash_move, ply);
+    moves.iter().map(|mv| move_to_uci(board, mv)).collect()
+}
+
+#[test]
+fn captures_by_mvv_lva() {
+    let board = board();
+    assert!(mvv_lva(&board, &uci(&board, "e4f5")) > mvv_lva(&board, &uci(&board, "e4d5")));
+    assert!(mvv_lva(&board, &uci(&board, "e4d5")) > mvv_lva(&board, &uci(&board, "c3d5")));
+    assert!(mvv_lva(&board, &uci(&board, "c3d5")) > mvv_lva(&board, &uci(&board, "d1d5")));
+
+    let mut captures: Vec<Move> = ["d1d5", "c3d5", "e4f5", "e4d5"].iter().map(|mv| uci(&board, mv)).collect();
+    order_noisy(&board, &mut captures);
+    let captures: Vec<String> = captures.iter().map(|mv| move_to_uci(&board, mv)).collect();
+    assert_eq!(captures, ["e4f5", "e4d5", "c3d5", "d1d5"]);
+}
+
+#[test]
+fn hash_move_then_captures() {
+    let board = board();
+    let ordering = MoveOrdering::new();
+    let moves = ordered(&ordering, &board, Some(uci(&board, "e1e2")), 0);
+    assert_eq!(moves[..5], ["e1e2", "e4f5", "e4d5", "c3d5", "d1d5"]);
+}
+
+#[test]
+fn killers_countermove_and_history() {
+    let mut board = board();
+    board.make_move(uci(&board, "e1e2"));
+    board.make_move(uci(&board, "e8e7"));
+    let mut ordering = MoveOrdering::new();
+
+    let (first, second, counter, good) =
+        (uci(&board, "d1d2"), uci(&board, "c3b5"), uci(&board, "e2e3"), uci(&board, "c3a4"));
+    let tried = [good, uci(&board, "d1d4")];
+    ordering.cutoff(&board, good, 10, 4, &tried);
+    assert_eq!(ordering.history(Color::White, &good), 16);
+    assert_eq!(ordering.history(Color::White, &tried[1]), -16);
+
+    ordering.cutoff(&board, counter, 7, 1, &[]);
+    ordering.cutoff(&board, second, 3, 1, &[]);
+    ordering.cutoff(&board, first, 3, 1, &[]);
+    ordering.cutoff(&board, first, 3, 1, &[]);
+    assert_eq!(ordering.killers(3), [Some(first), Some(second)]);
+    assert_eq!(ordering.countermove(&board), Some(first));
+
+    // the countermove is the first killer as well, history sorts what is left
+    let moves = ordered(&ordering, &board, None, 3);
+    assert_eq!(moves[4..7], ["d1d2", "c3b5", "c3a4"]);
+    assert_eq!(moves.last().unwrap(), "d1d4");
+
+    ordering.cutoff(&board, counter, 7, 1, &[]);
+    let moves = ordered(&ordering, &board, None, 0);
+    assert_eq!(moves[4..6], ["e2e3", "c3a4"]);
+
+    ordering.clear();
+    assert_eq!(ordering.killers(3), [None, None]);
+    assert_eq!(ordering.countermove(&board), None);
+    assert_eq!(ordering.history(Color::White, &good), 0);
+}
+
+#[test]
+fn no_countermove_after_a_null_move() {
+    let mut board = board();
+    board.make_move(uci(&board, "e1e2"));
+    let mut ordering = MoveOrdering::new();
+    let counter = uci(&board, "e8e7");
+    ordering.cutoff(&board, counter, 7, 1, &[]);
+
+    // after black passes, e1e2 is white's own move and nothing white could answer
+    board.make_null_move();
+    assert_eq!(ordering.countermove(&board), None);
+    ordering.cutoff(&board, uci(&board, "d1d2"), 7, 1, &[]);
+    board.unmake_null_move();
+    assert_eq!(ordering.countermove(&board), Some(counter));
+}
@@ -0,0 +1,96 @@
+use chess_engine::{
+    components::chess::{Board, Color},
+    outcome::outcome::{DrawReason, GameOutcome},
+    san::san::parse_san,
+};
+
+fn outcome(fen: &str) -> Option<GameOutcome> {
+    Board::build_from_fen(fen.to_string()).unwrap().outcome()
+}
+
+fn play(board: &mut Board, moves: &[&str]) {
+    for san in moves {
+        let mv = parse_san(board, san).unwrap();
+        board.make_move(mv);
+    }
+}
+
+#[test]
+fn checkmate() {
+    let mate = outcome("R5k1/5ppp/8/8/8/8/8/6K1 b - - 0 1").unwrap();
+    assert_eq!(mate, GameOutcome::Checkmate { winner: Color::White });
+    assert_eq!(mate.to_string(), "1-0 {White mates}");
+    assert_eq!(outcome("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1"), None);
+}
+
+#[test]
+fn stalemate() {
+    let draw = outcome("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1").unwrap();
+    assert_eq!(draw, GameOutcome::Draw(DrawReason::Stalemate));
+    assert_eq!(draw.to_string(), "1/2-1/2 {Draw by stalemate}");
+}
+
+#[test]
+fn fifty_move_rule() {
+    assert_eq!(outcome("4k3/8/8/8/8/8/4R3/4K3 w - - 99 80"), None);
+    assert_eq!(
+        outcome("4k3/8/8/8/8/8/4R3/4K3 w - - 100 80"),
+        Some(GameOutcome::Draw(DrawReason::FiftyMoveRule))
+    );
+
+    let mut board = Board::build_from_fen("4k3/8/8/8/8/8/4R3/4K3 w - - 99 80".to_string()).unwrap();
+    play(&mut board, &["Ra2"]);
+    assert_eq!(board.outcome(), Some(GameOutcome::Draw(DrawReason::FiftyMoveRule)));
+}
+
+#[test]
+fn checkmate_comes_before_the_fifty_move_rule() {
+    assert_eq!(
+        outcome("R5k1/5ppp/8/8/8/8/8/6K1 b - - 100 80"),
+        Some(GameOutcome::Checkmate { winner: Color::White })
+    );
+}
+
+#[test]
+fn threefold_repetition() {
+    let mut board = Board::build();
+    play(&mut board, &["Nf3", "Nf6", "Ng1", "Ng8"]);
+    assert_eq!(board.repetitions(), 2);
+    assert_eq!(board.outcome(), None);
+
+    play(&mut board, &["Nf3", "Nf6", "Ng1", "Ng8"]);
+    assert_eq!(board.repetitions(), 3);
+    assert_eq!(board.outcome(), Some(GameOutcome::Draw(DrawReason::ThreefoldRepetition)));
+}
+
+#[test]
+fn en_passant_square_without_a_capture_still_repeats() {
+    // the position after 2...e5 has e6 as its en passant square, but no white pawn can take
+    let mut board = Board::build();
+    play(&mut board, &["e4", "e5", "Nf3", "Nf6", "Ng1", "Ng8", "Nf3", "Nf6", "Ng1", "Ng8"]);
+    assert_eq!(board.repetitions(), 3);
+    assert_eq!(board.outcome(), Some(GameOutcome::Draw(DrawReason::ThreefoldRepetition)));
+}
+
+#[test]
+fn repetitions_do_not_reach_past_a_pawn_move() {
+    let mut board = Board::build();
+    play(&mut board, &["Nf3", "Nf6", "Ng1", "Ng8", "e3", "e6", "Nf3", "Nf6", "Ng1", "Ng8"]);
+    assert_eq!(board.repetitions(), 2);
+    assert_eq!(board.outcome(), None);
+}
+
+#[test]
+fn insufficient_material() {
+    let draw = Some(GameOutcome::Draw(DrawReason::InsufficientMaterial));
+    assert_eq!(outcome("4k3/8/8/8/8/8/8/4K3 w - - 0 1"), draw);
+    assert_eq!(outcome("4k3/8/8/8/8/8/8/1N2K3 w - - 0 1"), draw);
+    assert_eq!(outcome("4k3/8/8/8/8/8/8/2B1K3 w - - 0 1"), draw);
+    // bishops on c1 and f8 both live on dark squares
+    assert_eq!(outcome("4kb2/8/8/8/8/8/8/2B1K3 w - - 0 1"), draw);
+
+    // c8 is light, the bishops can still mate together
+    assert_eq!(outcome("2b1k3/8/8/8/8/8/8/2B1K3 w - - 0 1"), None);
+    assert_eq!(outcome("4k3/8/8/8/8/8/8/1NN1K3 w - - 0 1"), None);
+    assert_eq!(outcome("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1"), None);
+}
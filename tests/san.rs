@@ -0,0 +1,173 @@
+use chess_engine::{
+    components::chess::{Board, PieceType},
+    fen::fen::get_string_from_square,
+    moves::moves::{get_legal_moves, CastleSide, Move},
+    san::san::{move_to_san, parse_san, SanError},
+};
+
+fn board(fen: &str) -> Board {
+    Board::build_from_fen(fen.to_string()).unwrap()
+}
+
+fn squares(mv: Move) -> ((u8, u8), (u8, u8)) {
+    (mv.source, mv.destination)
+}
+
+#[test]
+fn pawn_and_piece_moves() {
+    let start = Board::build();
+    assert_eq!(squares(parse_san(&start, "e4").unwrap()), ((2, 5), (4, 5)));
+    assert_eq!(squares(parse_san(&start, "Nf3").unwrap()), ((1, 7), (3, 6)));
+    assert_eq!(squares(parse_san(&start, " Nc3\n").unwrap()), ((1, 2), (3, 3)));
+    assert_eq!(squares(parse_san(&start, "Nf3!?").unwrap()), ((1, 7), (3, 6)));
+    assert_eq!(squares(parse_san(&start, "e4!!").unwrap()), ((2, 5), (4, 5)));
+}
+
+#[test]
+fn captures_and_check_marks() {
+    let position = board("rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 2");
+    assert_eq!(squares(parse_san(&position, "exd5").unwrap()), ((4, 5), (5, 4)));
+    assert_eq!(squares(parse_san(&position, "ed5").unwrap()), ((4, 5), (5, 4)));
+    assert_eq!(parse_san(&position, "exe5"), Err(SanError::IllegalMove("exe5".to_string())));
+    // a pawn move without its file never captures
+    assert_eq!(parse_san(&position, "d5"), Err(SanError::IllegalMove("d5".to_string())));
+    // pieces may still leave out the x
+    let position = board("rnbqkbnr/ppp1pppp/8/3p4/4P3/2N5/PPPP1PPP/R1BQKBNR w KQkq - 0 2");
+    assert_eq!(squares(parse_san(&position, "Nd5").unwrap()), ((3, 3), (5, 4)));
+
+    let position = board("rnbqkbnr/ppppp2p/5p2/6p1/4P3/8/PPPP1PPP/RNBQKBNR w KQkq g6 0 3");
+    let mate = parse_san(&position, "Qh5#").unwrap();
+    assert_eq!(squares(mate), ((1, 4), (5, 8)));
+    assert_eq!(parse_san(&position, "Qh5+"), Ok(mate));
+}
+
+#[test]
+fn en_passant() {
+    let position = board("rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3");
+    let mv = parse_san(&position, "exf6").unwrap();
+    assert_eq!(squares(mv), ((5, 5), (6, 6)));
+    assert!(mv.takes);
+}
+
+#[test]
+fn disambiguation() {
+    // rooks on a1 and h1 can both reach d1, knights on b1 and b5 can both reach c3
+    let position = board("4k3/8/8/8/8/8/4K3/R6R w - - 0 1");
+    assert_eq!(parse_san(&position, "Rd1"), Err(SanError::AmbiguousMove("Rd1".to_string())));
+    assert_eq!(squares(parse_san(&position, "Rad1").unwrap()), ((1, 1), (1, 4)));
+    assert_eq!(squares(parse_san(&position, "Rhd1").unwrap()), ((1, 8), (1, 4)));
+
+    let position = board("4k3/8/8/1N6/8/8/8/1N2K3 w - - 0 1");
+    assert_eq!(parse_san(&position, "Nc3"), Err(SanError::AmbiguousMove("Nc3".to_string())));
+    assert_eq!(squares(parse_san(&position, "N1c3").unwrap()), ((1, 2), (3, 3)));
+    assert_eq!(squares(parse_san(&position, "N5c3").unwrap()), ((5, 2), (3, 3)));
+    assert_eq!(squares(parse_san(&position, "Nb5c3").unwrap()), ((5, 2), (3, 3)));
+
+    // three queens that need a full square
+    let position = board("4k3/8/8/8/Q6Q/8/8/4K2Q w - - 0 1");
+    assert_eq!(parse_san(&position, "Qe4"), Err(SanError::AmbiguousMove("Qe4".to_string())));
+    assert_eq!(parse_san(&position, "Qhe4"), Err(SanError::AmbiguousMove("Qhe4".to_string())));
+    assert_eq!(squares(parse_san(&position, "Qh4e4").unwrap()), ((4, 8), (4, 5)));
+}
+
+#[test]
+fn promotions() {
+    let position = board("1n2k3/P7/8/8/8/8/8/4K3 w - - 0 1");
+    let mv = parse_san(&position, "a8=Q").unwrap();
+    assert_eq!((mv.destination, mv.promotion), ((8, 1), Some(PieceType::Queen)));
+    assert_eq!(parse_san(&position, "a8N").unwrap().promotion, Some(PieceType::Knight));
+    let mv = parse_san(&position, "axb8=R+").unwrap();
+    assert_eq!((mv.destination, mv.promotion), ((8, 2), Some(PieceType::Rook)));
+    assert_eq!(parse_san(&position, "a8"), Err(SanError::IllegalMove("a8".to_string())));
+    assert_eq!(parse_san(&position, "a8=K"), Err(SanError::InvalidSyntax("a8=K".to_string())));
+}
+
+#[test]
+fn castling() {
+    let position = board("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1");
+    for san in ["O-O", "0-0", "O-O+"] {
+        assert_eq!(parse_san(&position, san).unwrap().castle, Some(CastleSide::KingSide));
+    }
+    for san in ["O-O-O", "0-0-0"] {
+        assert_eq!(parse_san(&position, san).unwrap().castle, Some(CastleSide::QueenSide));
+    }
+    assert_eq!(parse_san(&position, "Kg1"), Err(SanError::IllegalMove("Kg1".to_string())));
+
+    let position = board("r3k2r/8/8/8/8/8/8/R3K2R w Qkq - 0 1");
+    assert_eq!(parse_san(&position, "O-O"), Err(SanError::IllegalMove("O-O".to_string())));
+}
+
+#[test]
+fn errors() {
+    let start = Board::build();
+    for san in ["", "x", "Pe4", "e9", "Nf3g", "Zf3", "e4e5", "O-O-O-O"] {
+        assert_eq!(parse_san(&start, san), Err(SanError::InvalidSyntax(san.to_string())), "{}", san);
+    }
+    for san in ["e5", "Nf4", "Ke2", "Bb5", "O-O"] {
+        assert_eq!(parse_san(&start, san), Err(SanError::IllegalMove(san.to_string())), "{}", san);
+    }
+}
+
+#[test]
+fn san_output() {
+    let cases = [
+        ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", "g1f3", "Nf3"),
+        ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", "e2e4", "e4"),
+        ("rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 2", "e4d5", "exd5"),
+        ("rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3", "e5f6", "exf6"),
+        ("rnbqkbnr/ppppp2p/5p2/6p1/4P3/8/PPPP1PPP/RNBQKBNR w KQkq g6 0 3", "d1h5", "Qh5#"),
+        ("4k3/8/8/8/8/8/4K3/R6R w - - 0 1", "a1d1", "Rad1"),
+        ("4k3/8/8/8/8/8/4K3/R6R w - - 0 1", "a1a8", "Ra8+"),
+        ("4k3/8/8/1N6/8/8/8/1N2K3 w - - 0 1", "b1c3", "N1c3"),
+        ("4k3/8/8/1N6/8/8/8/1N2K3 w - - 0 1", "b1d2", "Nd2"),
+        ("4k3/8/8/8/Q6Q/8/8/4K2Q w - - 0 1", "h4e4", "Qh4e4+"),
+        ("4k3/8/8/8/Q6Q/8/8/4K2Q w - - 0 1", "a4e4", "Qae4+"),
+        ("4k3/8/8/8/Q6Q/8/8/4K2Q w - - 0 1", "h1e4", "Q1e4+"),
+        ("1n2k3/P7/8/8/8/8/8/4K3 w - - 0 1", "a7b8", "axb8=Q+"),
+        ("1n2k3/P7/8/8/8/8/8/4K3 w - - 0 1", "a7a8", "a8=Q"),
+        ("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1", "e1g1", "O-O"),
+        ("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1", "e1c1", "O-O-O"),
+        ("r3k3/8/8/8/8/8/8/R3K2R b KQq - 0 1", "e8c8", "O-O-O"),
+    ];
+
+    for (fen, coordinates, expected) in cases {
+        let position = board(fen);
+        let mv = get_legal_moves(&position)
+            .into_iter()
+            .find(|mv| {
+                coordinates
+                    == format!(
+                        "{}{}",
+                        get_string_from_square(Some(mv.source)),
+                        get_string_from_square(Some(mv.destination))
+                    )
+                    && mv.promotion.is_none_or(|piece_type| piece_type == PieceType::Queen)
+            })
+            .unwrap();
+        assert_eq!(move_to_san(&position, &mv), expected, "{}", fen);
+    }
+}
+
+#[test]
+fn every_legal_move_round_trips_through_san() {
+    for fen in [
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        "4k3/8/8/8/Q6Q/8/8/Q3K2Q w - - 0 1",
+    ] {
+        let mut position = board(fen);
+        for mv in get_legal_moves(&position) {
+            let san = move_to_san(&position, &mv);
+            assert_eq!(parse_san(&position, &san), Ok(mv), "{} {}", fen, san);
+
+            // and one ply deeper
+            position.make_move(mv);
+            for reply in get_legal_moves(&position) {
+                let san = move_to_san(&position, &reply);
+                assert_eq!(parse_san(&position, &san), Ok(reply), "{} {}", fen, san);
+            }
+            position.unmake_move();
+        }
+    }
+}
@@ -0,0 +1,215 @@
+use std::{
+    sync::atomic::Ordering,
+    time::{Duration, Instant},
+};
+
+use chess_engine::{
+    components::chess::{Board, Color},
+    moves::moves::get_legal_moves,
+    outcome::outcome::{DrawReason, GameOutcome},
+    search::search::{is_mate_score, mate_in, Search, SearchLimits, SearchOptions, SearchResult, SearchStats, MATE},
+    uci::uci::{move_to_uci, parse_uci_move},
+    zobrist::zobrist::compute_hash,
+};
+
+fn search(fen: &str, depth: u32) -> (Board, SearchResult) {
+    let board = Board::build_from_fen(fen.to_string()).unwrap();
+    let limits = SearchLimits { depth: Some(depth), ..SearchLimits::default() };
+    let result = Search::new().run(&board, limits, |_| {});
+    (board, result)
+}
+
+#[test]
+fn mate_scores() {
+    assert_eq!(mate_in(MATE - 1), Some(1));
+    assert_eq!(mate_in(MATE - 3), Some(2));
+    assert_eq!(mate_in(-MATE + 2), Some(-1));
+    assert_eq!(mate_in(-MATE + 4), Some(-2));
+    assert_eq!(mate_in(250), None);
+    assert!(is_mate_score(MATE - 10) && !is_mate_score(900));
+}
+
+#[test]
+fn finds_mate_in_one() {
+    let (board, result) = search("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1", 3);
+    assert_eq!(move_to_uci(&board, &result.best_move.unwrap()), "a1a8");
+    assert_eq!(mate_in(result.score), Some(1));
+}
+
+#[test]
+fn finds_mate_in_two() {
+    // Rd8+ Rxd8 Qxd8#
+    let (board, result) = search("2r3k1/5ppp/8/8/8/8/3R1PPP/3Q2K1 w - - 0 1", 4);
+    assert_eq!(mate_in(result.score), Some(2), "{:?}", result);
+    let mut board = board.clone();
+    for mv in &result.pv {
+        assert!(get_legal_moves(&board).contains(mv));
+        board.make_move(*mv);
+    }
+    assert_eq!(board.outcome(), Some(GameOutcome::Checkmate { winner: Color::White }));
+}
+
+#[test]
+fn takes_a_free_queen() {
+    let (board, result) = search("4k3/8/8/3q4/8/8/8/3QK3 w - - 0 1", 2);
+    assert_eq!(move_to_uci(&board, &result.best_move.unwrap()), "d1d5");
+    assert!(result.score > 800);
+}
+
+#[test]
+fn does_not_stalemate_when_winning() {
+    let (mut board, result) = search("7k/8/6Q1/8/8/8/8/6K1 w - - 0 1", 3);
+    board.make_move(result.best_move.unwrap());
+    assert_ne!(board.outcome(), Some(GameOutcome::Draw(DrawReason::Stalemate)));
+    assert!(result.score > 0);
+}
+
+#[test]
+fn result_and_limits() {
+    let mut depths = vec![];
+    let board = Board::build();
+    let limits = SearchLimits { depth: Some(3), ..SearchLimits::default() };
+    let result = Search::new().run(&board, limits, |result| depths.push(result.depth));
+    assert_eq!(depths, [1, 2, 3]);
+    assert_eq!(result.depth, 3);
+    assert_eq!(result.best_move, result.pv.first().copied());
+    assert!(result.nodes > 0);
+
+    // no legal move, no best move
+    let (_, result) = search("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1", 3);
+    assert_eq!(result.best_move, None);
+
+    // a raised stop flag still leaves a move to play
+    let mut search = Search::new();
+    search.stop_flag().store(true, Ordering::Relaxed);
+    let result = search.run(&board, SearchLimits::default(), |_| {});
+    assert!(result.best_move.is_some());
+
+    let limits = SearchLimits { movetime: Some(Duration::from_millis(50)), ..SearchLimits::default() };
+    let result = Search::new().run(&board, limits, |_| {});
+    assert!(result.best_move.is_some() && result.time < Duration::from_secs(2));
+}
+
+#[test]
+fn quiescence_sees_the_recapture() {
+    // the pawn on d5 is defended, a one ply search must not grab it with the queen
+    let (board, result) = search("4k3/8/2p5/3p4/8/8/8/3QK3 w - - 0 1", 1);
+    assert_ne!(move_to_uci(&board, &result.best_move.unwrap()), "d1d5");
+    assert!(result.score > 500 && result.score < 1100, "{}", result.score);
+}
+
+#[test]
+fn quiescence_options() {
+    let board = Board::build_from_fen("2r3k1/5ppp/8/8/8/8/3R1PPP/3Q2K1 w - - 0 1".to_string()).unwrap();
+    let limits = SearchLimits { depth: Some(4), ..SearchLimits::default() };
+    for (quiescence_checks, delta_pruning) in [(true, true), (false, false)] {
+        let mut search = Search::new();
+        search.options.quiescence_checks = quiescence_checks;
+        search.options.delta_pruning = delta_pruning;
+        let result = search.run(&board, limits, |_| {});
+        assert_eq!(mate_in(result.score), Some(2));
+    }
+}
+
+#[test]
+fn tactical_position_searches_quickly() {
+    // Kiwipete is full of captures, unordered they blow the quiescence search up
+    let start = Instant::now();
+    let (_, result) = search("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1", 3);
+    assert_eq!(result.depth, 3);
+    assert!(result.nodes < 100_000, "{}", result.nodes);
+    assert!(start.elapsed() < Duration::from_secs(20), "{:?}", start.elapsed());
+}
+
+// a position where the score keeps moving between depths
+const TACTICAL: &str = "5rk1/1ppb3p/p1pb4/6q1/3P1p1r/2P1R2P/PP1BQ1P1/5RKN w - - 0 1";
+
+// reads the statistic belonging to one technique
+type Counter = fn(&SearchStats) -> u64;
+
+fn search_with(fen: &str, depth: u32, options: SearchOptions) -> SearchResult {
+    let board = Board::build_from_fen(fen.to_string()).unwrap();
+    let mut search = Search::new();
+    search.options = options;
+    search.run(&board, SearchLimits { depth: Some(depth), ..SearchLimits::default() }, |_| {})
+}
+
+#[test]
+fn selective_techniques_switch_on_one_at_a_time() {
+    let none = SearchOptions {
+        null_move: false,
+        late_move_reductions: false,
+        futility_pruning: false,
+        reverse_futility_pruning: false,
+        razoring: false,
+        aspiration_windows: false,
+        ..SearchOptions::default()
+    };
+    let plain = search_with(TACTICAL, 5, none);
+    assert_eq!(plain.stats, SearchStats::default());
+
+    let techniques: [(SearchOptions, Counter); 5] = [
+        (SearchOptions { null_move: true, ..none }, |stats| stats.null_move_cutoffs),
+        (SearchOptions { late_move_reductions: true, ..none }, |stats| stats.reductions),
+        (SearchOptions { futility_pruning: true, ..none }, |stats| stats.futility_prunes),
+        (SearchOptions { reverse_futility_pruning: true, ..none }, |stats| stats.reverse_futility_prunes),
+        (SearchOptions { razoring: true, ..none }, |stats| stats.razoring_cutoffs),
+    ];
+    for (options, counter) in techniques {
+        let result = search_with(TACTICAL, 5, options);
+        let count = counter(&result.stats);
+        assert!(count > 0, "{:?}", result.stats);
+        let total = result.stats.null_move_cutoffs
+            + result.stats.reductions
+            + result.stats.futility_prunes
+            + result.stats.reverse_futility_prunes
+            + result.stats.razoring_cutoffs
+            + result.stats.aspiration_researches;
+        assert_eq!(total, count, "{:?}", result.stats);
+        assert!(result.nodes < plain.nodes);
+    }
+}
+
+#[test]
+fn aspiration_windows_are_widened_when_the_score_moves() {
+    let result = search_with(TACTICAL, 5, SearchOptions::default());
+    assert!(result.stats.aspiration_researches > 0, "{:?}", result.stats);
+
+    let options = SearchOptions { aspiration_windows: false, ..SearchOptions::default() };
+    let without = search_with(TACTICAL, 5, options);
+    assert_eq!(without.stats.aspiration_researches, 0);
+}
+
+#[test]
+fn null_move_passes_the_turn() {
+    let mut board = Board::build();
+    let mv = parse_uci_move(&board, "e2e4").unwrap();
+    board.make_move(mv);
+    let fen = board.to_fen();
+    board.make_null_move();
+    assert_eq!(board.to_fen(), "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 1");
+    assert_eq!(board.hash, compute_hash(&board));
+    assert_eq!(board.last_move(), None);
+    board.unmake_null_move();
+    assert_eq!(board.to_fen(), fen);
+    assert_eq!(board.hash, compute_hash(&board));
+    assert_eq!(board.last_move(), Some(&mv));
+}
+
+#[test]
+fn no_null_move_with_pawns_alone() {
+    // zugzwang is everyday business in pawn endings
+    let result = search_with("8/8/1p1k4/p7/P1P5/1P3K2/8/8 w - - 0 1", 6, SearchOptions::default());
+    assert_eq!(result.stats.null_move_cutoffs, 0);
+    assert!(result.stats.reductions > 0);
+}
+
+#[test]
+fn selective_search_still_finds_mates() {
+    // Qc4+ Kd2 Qe2#
+    let fen = "5k2/6pp/p1qN4/1p1p4/3P4/2PKP2Q/PP3r2/3R4 b - - 0 1";
+    let board = Board::build_from_fen(fen.to_string()).unwrap();
+    let result = search_with(fen, 6, SearchOptions::default());
+    assert_eq!(mate_in(result.score), Some(2));
+    assert_eq!(move_to_uci(&board, &result.best_move.unwrap()), "c6c4");
+}
@@ -0,0 +1,46 @@
+use chess_engine::{components::chess::Board, see::see::see, uci::uci::parse_uci_move};
+
+fn see_of(fen: &str, mv: &str) -> i32 {
+    let board = Board::build_from_fen(fen.to_string()).unwrap();
+    let mv = parse_uci_move(&board, mv).unwrap();
+    see(&board, &mv)
+}
+
+#[test]
+fn simple_captures() {
+    // undefended pawn
+    assert_eq!(see_of("4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1", "e4d5"), 100);
+    // pawn defended by a pawn costs the queen
+    assert_eq!(see_of("4k3/8/2p5/3p4/8/8/8/3QK3 w - - 0 1", "d1d5"), -800);
+    // a quiet move to an attacked square loses the piece
+    assert_eq!(see_of("4k3/8/2p5/8/8/8/8/3QK3 w - - 0 1", "d1d5"), -900);
+    assert_eq!(see_of("4k3/8/8/8/8/8/8/3QK3 w - - 0 1", "d1d5"), 0);
+}
+
+#[test]
+fn exchanges_stop_when_they_stop_paying() {
+    // after NxN the rook and queen would only lose more, so white stops
+    assert_eq!(see_of("1k1r3q/1ppn3p/p4b2/4p3/8/P2N2P1/1PP1R1BP/2K1Q3 w - - 0 1", "d3e5"), -220);
+    // the rook on d8 does not defend e5, the pawn is simply won
+    assert_eq!(see_of("1k1r4/1pp4p/p7/4p3/8/P5P1/1PP4P/2K1R3 w - - 0 1", "e1e5"), 100);
+}
+
+#[test]
+fn x_rays_join_the_exchange() {
+    // the second rook backs up the first one through it
+    assert_eq!(see_of("4k3/4r3/8/4p3/8/8/4R3/4RK2 w - - 0 1", "e2e5"), 100);
+    assert_eq!(see_of("4k3/4r3/8/4p3/8/8/4R3/5K2 w - - 0 1", "e2e5"), -400);
+    // the queen behind the rook keeps the king from taking back
+    assert_eq!(see_of("4q1k1/4r3/8/8/8/8/4P3/4K3 b - - 0 1", "e7e2"), 100);
+    assert_eq!(see_of("6k1/4r3/8/8/8/8/4P3/4K3 b - - 0 1", "e7e2"), -400);
+}
+
+#[test]
+fn special_moves() {
+    assert_eq!(see_of("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1", "e5d6"), 100);
+    assert_eq!(see_of("4k3/P7/8/8/8/8/8/4K3 w - - 0 1", "a7a8q"), 800);
+    assert_eq!(see_of("r3k3/1P6/8/8/8/8/8/4K3 w - - 0 1", "b7a8n"), 500 + 220);
+    // the promoted queen is taken back
+    assert_eq!(see_of("1r2k3/P7/8/8/8/8/8/4K3 w - - 0 1", "a7a8q"), -100);
+    assert_eq!(see_of("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1", "e1g1"), 0);
+}
@@ -0,0 +1,104 @@
+use std::time::{Duration, Instant};
+
+use chess_engine::{
+    components::chess::{Board, Color},
+    search::search::{Search, SearchLimits},
+    time::time::{Clock, TimeManager, MOVE_OVERHEAD},
+    uci::uci::{parse_go, search_limits},
+};
+
+fn millis(millis: u64) -> Duration {
+    Duration::from_millis(millis)
+}
+
+fn clock(time: u64, increment: u64, moves_to_go: Option<u32>) -> Clock {
+    Clock {
+        time: millis(time),
+        increment: millis(increment),
+        moves_to_go,
+    }
+}
+
+#[test]
+fn sudden_death() {
+    let time = TimeManager::new(&clock(60_050, 0, None));
+    assert_eq!(time.soft(), millis(2000));
+    assert_eq!(time.hard(), millis(8000));
+}
+
+#[test]
+fn fischer_increment() {
+    let time = TimeManager::new(&clock(30_050, 1000, None));
+    assert_eq!(time.soft(), millis(2000));
+    assert_eq!(time.hard(), millis(8000));
+
+    // an increment larger than the clock is not spent before it arrives
+    let time = TimeManager::new(&clock(1050, 2000, None));
+    assert_eq!(time.hard(), millis(500));
+    assert!(time.soft() <= time.hard());
+}
+
+#[test]
+fn repeating_controls() {
+    let time = TimeManager::new(&clock(40_050, 0, Some(20)));
+    assert_eq!(time.soft(), millis(2000));
+    assert_eq!(time.hard(), millis(8000));
+
+    // the last move before the clock is topped up may use all of it
+    let last = TimeManager::new(&clock(2050, 0, Some(1)));
+    assert_eq!(last.hard(), millis(2000));
+    assert_eq!(last.soft(), millis(1000));
+    let second_last = TimeManager::new(&clock(2050, 0, Some(2)));
+    assert_eq!(second_last.hard(), millis(1000));
+}
+
+#[test]
+fn never_more_than_the_clock() {
+    for time in [0, 10, 50, 51, 100, 1000, 10_000, 1_000_000] {
+        for increment in [0, 100, 5000] {
+            for moves_to_go in [None, Some(1), Some(2), Some(40)] {
+                let manager = TimeManager::new(&clock(time, increment, moves_to_go));
+                assert!(manager.soft() <= manager.hard());
+                assert!(manager.hard() + MOVE_OVERHEAD <= millis(time.max(50)));
+            }
+        }
+    }
+}
+
+#[test]
+fn extensions_stop_at_the_hard_deadline() {
+    let mut time = TimeManager::new(&clock(60_050, 0, None));
+    time.extend();
+    assert_eq!(time.soft(), millis(3000));
+    for _ in 0..10 {
+        time.extend();
+    }
+    assert_eq!(time.soft(), time.hard());
+}
+
+#[test]
+fn go_hands_the_clock_to_the_time_manager() {
+    let go = parse_go(&["wtime", "60000", "btime", "30000", "winc", "1000", "binc", "500", "movestogo", "12"]);
+    let limits = search_limits(&go, Color::Black);
+    assert_eq!(limits.clock, Some(clock(30_000, 500, Some(12))));
+    assert_eq!(limits.movetime, None);
+
+    let limits = search_limits(&parse_go(&["movetime", "300"]), Color::White);
+    assert_eq!((limits.clock, limits.movetime), (None, Some(millis(300))));
+}
+
+#[test]
+fn clocked_search_keeps_to_the_deadline() {
+    let board = Board::build_from_fen(
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1".to_string(),
+    )
+    .unwrap();
+    let clock = clock(1050, 0, Some(1));
+    let hard = TimeManager::new(&clock).hard();
+
+    let start = Instant::now();
+    let result = Search::new().run(&board, SearchLimits { clock: Some(clock), ..SearchLimits::default() }, |_| {});
+    assert!(result.best_move.is_some());
+    // the deadline is only polled every so many nodes, the overhead covers that
+    assert!(start.elapsed() < hard + MOVE_OVERHEAD, "{:?}", start.elapsed());
+}
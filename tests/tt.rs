@@ -0,0 +1,87 @@
+use chess_engine::{
+    components::chess::Board,
+    moves::moves::get_legal_moves,
+    search::search::{Search, SearchLimits, MATE},
+    tt::tt::{Bound, TranspositionTable},
+};
+
+// keys that differ only in their low bits land in the same bucket
+const KEY: u64 = 0x8000_0000_0000_0000;
+
+#[test]
+fn store_and_probe() {
+    let mut tt = TranspositionTable::new(1);
+    let mv = get_legal_moves(&Board::build())[0];
+    assert_eq!(tt.probe(KEY, 0), None);
+
+    tt.store(KEY, 0, 5, 37, Bound::Exact, Some(mv));
+    let entry = tt.probe(KEY, 0).unwrap();
+    assert_eq!((entry.depth, entry.score, entry.bound, entry.best_move), (5, 37, Bound::Exact, Some(mv)));
+
+    // a later store without a move keeps the one already known
+    tt.store(KEY, 0, 6, -12, Bound::Upper, None);
+    let entry = tt.probe(KEY, 0).unwrap();
+    assert_eq!((entry.depth, entry.score, entry.bound, entry.best_move), (6, -12, Bound::Upper, Some(mv)));
+}
+
+#[test]
+fn mate_scores_are_relative_to_the_node() {
+    let mut tt = TranspositionTable::new(1);
+    // mate in 3 plies seen from ply 4 is mate in 3 plies seen from ply 1 as well
+    tt.store(KEY, 4, 3, MATE - 7, Bound::Exact, None);
+    assert_eq!(tt.probe(KEY, 1).unwrap().score, MATE - 4);
+    tt.store(KEY + 1, 4, 3, -MATE + 7, Bound::Exact, None);
+    assert_eq!(tt.probe(KEY + 1, 1).unwrap().score, -MATE + 4);
+}
+
+#[test]
+fn replacement_prefers_depth_and_age() {
+    let mut tt = TranspositionTable::new(1);
+    tt.store(KEY, 0, 8, 0, Bound::Exact, None);
+    tt.store(KEY + 1, 0, 2, 0, Bound::Exact, None);
+    // the bucket is full, the shallow entry makes room
+    tt.store(KEY + 2, 0, 3, 0, Bound::Exact, None);
+    assert!(tt.probe(KEY, 0).is_some());
+    assert!(tt.probe(KEY + 1, 0).is_none());
+    assert!(tt.probe(KEY + 2, 0).is_some());
+
+    // after enough searches even the deep entry is stale
+    for _ in 0..3 {
+        tt.new_search();
+    }
+    tt.store(KEY + 3, 0, 1, 0, Bound::Exact, None);
+    tt.store(KEY + 4, 0, 1, 0, Bound::Exact, None);
+    assert!(tt.probe(KEY, 0).is_none());
+}
+
+#[test]
+fn hashfull_and_clear() {
+    let mut tt = TranspositionTable::new(1);
+    assert_eq!(tt.hashfull(), 0);
+    for i in 0..200_000u64 {
+        tt.store(i.wrapping_mul(0x9E37_79B9_7F4A_7C15), 0, 1, 0, Bound::Exact, None);
+    }
+    assert!(tt.hashfull() > 500, "{}", tt.hashfull());
+    tt.new_search();
+    assert_eq!(tt.hashfull(), 0);
+    tt.clear();
+    assert_eq!(tt.hashfull(), 0);
+}
+
+#[test]
+fn search_reports_hashfull_and_reuses_the_table() {
+    let board = Board::build_from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1".to_string()).unwrap();
+    let limits = SearchLimits { depth: Some(3), ..SearchLimits::default() };
+    let mut search = Search::new();
+    search.set_hash_size(1);
+    let first = search.run(&board, limits, |_| {});
+    assert!(first.hashfull > 0);
+
+    let second = search.run(&board, limits, |_| {});
+    assert_eq!(second.score, first.score);
+    assert!(second.nodes < first.nodes);
+
+    search.clear();
+    let cleared = search.run(&board, limits, |_| {});
+    assert_eq!(cleared.nodes, first.nodes);
+}
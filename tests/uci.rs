@@ -0,0 +1,195 @@
+mod common;
+
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use chess_engine::{
+    components::chess::{Board, PieceType},
+    moves::moves::{get_legal_moves, CastleSide},
+    uci::uci::{move_to_uci, parse_go, parse_position, parse_uci_move, uci_loop, Go, UciEngine, UciMoveError},
+};
+use common::SharedBuffer;
+
+fn board(fen: &str) -> Board {
+    Board::build_from_fen(fen.to_string()).unwrap()
+}
+
+#[test]
+fn coordinate_moves() {
+    let start = Board::build();
+    let mv = parse_uci_move(&start, "e2e4").unwrap();
+    assert_eq!((mv.source, mv.destination), ((2, 5), (4, 5)));
+    assert_eq!(move_to_uci(&start, &mv), "e2e4");
+
+    let position = board("1n2k3/P7/8/8/8/8/8/4K3 w - - 0 1");
+    let mv = parse_uci_move(&position, "a7b8n").unwrap();
+    assert_eq!((mv.destination, mv.promotion), ((8, 2), Some(PieceType::Knight)));
+    assert_eq!(move_to_uci(&position, &mv), "a7b8n");
+    assert_eq!(parse_uci_move(&position, "a7a8"), Err(UciMoveError::IllegalMove("a7a8".to_string())));
+}
+
+#[test]
+fn castling_is_the_king_move() {
+    let position = board("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1");
+    for text in ["e1g1", "e1h1"] {
+        let mv = parse_uci_move(&position, text).unwrap();
+        assert_eq!(mv.castle, Some(CastleSide::KingSide));
+        assert_eq!(move_to_uci(&position, &mv), "e1g1");
+    }
+    let mv = parse_uci_move(&position, "e1c1").unwrap();
+    assert_eq!(mv.castle, Some(CastleSide::QueenSide));
+
+    // a king already on g1 takes its rook in normal chess too, g1g1 would be a null move
+    let position = board("4k3/8/8/8/8/8/8/6KR w K - 0 1");
+    let mv = parse_uci_move(&position, "g1h1").unwrap();
+    assert_eq!(mv.castle, Some(CastleSide::KingSide));
+    assert_eq!(move_to_uci(&position, &mv), "g1h1");
+
+    // in chess960 the king takes its own rook, b1c1 stays a plain king move
+    let mut position = board("4k3/8/8/8/8/8/8/RK5R w KQ - 0 1");
+    position.chess960 = true;
+    let mv = parse_uci_move(&position, "b1a1").unwrap();
+    assert_eq!((mv.castle, mv.destination), (Some(CastleSide::QueenSide), (1, 3)));
+    assert_eq!(move_to_uci(&position, &mv), "b1a1");
+    assert_eq!(parse_uci_move(&position, "b1c1").unwrap().castle, None);
+    assert_eq!(move_to_uci(&position, &parse_uci_move(&position, "b1h1").unwrap()), "b1h1");
+}
+
+#[test]
+fn malformed_and_illegal_moves() {
+    let start = Board::build();
+    for text in ["", "e2", "e2e", "e2e9", "i2e4", "e7e8k", "e2e4qq", "e2-e4"] {
+        assert_eq!(parse_uci_move(&start, text), Err(UciMoveError::InvalidSyntax(text.to_string())), "{}", text);
+    }
+    for text in ["e2e5", "e1g1", "g1g3", "e7e5"] {
+        assert_eq!(parse_uci_move(&start, text), Err(UciMoveError::IllegalMove(text.to_string())), "{}", text);
+    }
+}
+
+#[test]
+fn every_legal_move_round_trips() {
+    for fen in [
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+        "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9",
+    ] {
+        for chess960 in [false, true] {
+            let mut position = board(fen);
+            position.chess960 = chess960;
+            for mv in get_legal_moves(&position) {
+                let text = move_to_uci(&position, &mv);
+                assert_eq!(parse_uci_move(&position, &text), Ok(mv), "{} {}", fen, text);
+            }
+        }
+    }
+}
+
+fn session(script: &str) -> Vec<String> {
+    let buffer = SharedBuffer::default();
+    uci_loop(script.as_bytes(), buffer.clone());
+    buffer.lines()
+}
+
+#[test]
+fn handshake() {
+    let lines = session("uci\nisready\nquit\n");
+    assert!(lines[0].starts_with("id name "));
+    assert!(lines.contains(&"option name UCI_Chess960 type check default false".to_string()));
+    assert!(lines.iter().any(|line| line.starts_with("option name Hash type spin")));
+    let uciok = lines.iter().position(|line| line == "uciok").unwrap();
+    assert_eq!(lines[uciok + 1], "readyok");
+}
+
+// plays the script, then waits for the search to answer before it quits
+fn session_until_bestmove(script: &str) -> Vec<String> {
+    let buffer = SharedBuffer::default();
+    let mut engine = UciEngine::new(buffer.clone());
+    for line in script.lines() {
+        engine.handle(line);
+    }
+
+    let started = Instant::now();
+    let lines = loop {
+        let lines = buffer.lines();
+        if lines.iter().any(|line| line.starts_with("bestmove ")) {
+            break lines;
+        }
+        assert!(started.elapsed() < Duration::from_secs(30), "no bestmove: {:?}", lines);
+        thread::sleep(Duration::from_millis(5));
+    };
+    engine.handle("quit");
+    lines
+}
+
+#[test]
+fn go_ends_with_a_legal_bestmove() {
+    let lines = session_until_bestmove("setoption name Hash value 1\nucinewgame\nposition startpos moves e2e4 e7e5\ngo depth 4\n");
+    let last = lines.last().unwrap();
+    let best = last.strip_prefix("bestmove ").unwrap();
+
+    let mut board = Board::build();
+    for text in ["e2e4", "e7e5"] {
+        let mv = parse_uci_move(&board, text).unwrap();
+        board.make_move(mv);
+    }
+    assert!(parse_uci_move(&board, best).is_ok(), "{}", best);
+
+    // the last info line is the finished fourth iteration and its pv starts with the bestmove
+    let info = lines.iter().rev().find(|line| line.starts_with("info ")).unwrap();
+    let tokens: Vec<&str> = info.split_whitespace().collect();
+    let at = |name: &str| tokens.iter().position(|token| *token == name).unwrap();
+    let field = |name: &str| tokens[at(name) + 1];
+    assert_eq!(field("depth"), "4", "{}", info);
+    assert!(matches!(field("score"), "cp" | "mate"), "{}", info);
+    assert!(tokens[at("score") + 2].parse::<i32>().is_ok(), "{}", info);
+    assert!(field("nodes").parse::<u64>().unwrap() > 0, "{}", info);
+    assert_eq!(field("pv"), best, "{}", info);
+}
+
+#[test]
+fn infinite_search_waits_for_stop() {
+    let lines = session("position fen 4k3/8/8/8/8/8/8/4K2R w K - 0 1\ngo infinite\nstop\nisready\n");
+    let bestmove = lines.iter().position(|line| line.starts_with("bestmove ")).unwrap();
+    assert_eq!(lines[bestmove + 1], "readyok");
+}
+
+#[test]
+fn no_legal_move() {
+    let lines = session("position fen 7k/5Q2/6K1/8/8/8/8/8 b - - 0 1\ngo depth 1\n");
+    assert_eq!(lines.last().unwrap(), "bestmove 0000");
+}
+
+#[test]
+fn go_parameters() {
+    let go = parse_go(&["wtime", "-20", "btime", "3000", "winc", "5", "binc", "7", "movestogo", "12", "depth", "6", "nodes", "1000", "movetime", "250", "infinite"]);
+    assert_eq!(
+        go,
+        Go {
+            wtime: Some(0),
+            btime: Some(3000),
+            winc: Some(5),
+            binc: Some(7),
+            movestogo: Some(12),
+            depth: Some(6),
+            nodes: Some(1000),
+            movetime: Some(250),
+            infinite: true,
+        }
+    );
+}
+
+#[test]
+fn positions() {
+    let board = parse_position(&["startpos", "moves", "e2e4", "c7c5", "g1f3"], false).unwrap();
+    assert_eq!(board.to_fen(), "rnbqkbnr/pp1ppppp/8/2p5/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2");
+
+    let fen = "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1";
+    let args: Vec<&str> = ["fen"].into_iter().chain(fen.split(' ')).chain(["moves", "e1h1"]).collect();
+    assert_eq!(parse_position(&args, false).unwrap().to_fen(), "r3k2r/8/8/8/8/8/8/R4RK1 b kq - 1 1");
+    assert!(parse_position(&args, true).unwrap().chess960);
+
+    assert!(parse_position(&["startpos", "moves", "e2e5"], false).is_none());
+    assert!(parse_position(&["fen", "8/8/8", "w", "-", "-", "0", "1"], false).is_none());
+}
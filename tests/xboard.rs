@@ -0,0 +1,176 @@
+mod common;
+
+use std::time::{Duration, Instant};
+
+use chess_engine::{
+    components::chess::Board,
+    moves::moves::CastleSide,
+    uci::uci::{parse_uci_move, Go},
+    xboard::xboard::{parse_level, xboard_loop, TimeControl, XboardEngine},
+};
+use common::SharedBuffer;
+
+fn session(script: &str) -> Vec<String> {
+    let buffer = SharedBuffer::default();
+    xboard_loop(script.as_bytes(), buffer.clone());
+    buffer.lines()
+}
+
+fn engine_moves(lines: &[String]) -> Vec<&str> {
+    lines.iter().filter_map(|line| line.strip_prefix("move ")).collect()
+}
+
+#[test]
+fn handshake() {
+    let lines = session("xboard\nprotover 2\nping 7\n");
+    assert!(lines[0].starts_with("feature "));
+    assert!(lines[0].contains("usermove=1") && lines[0].contains("setboard=1") && lines[0].ends_with("done=1"));
+    assert_eq!(lines[1], "pong 7");
+}
+
+#[test]
+fn engine_answers_user_moves_as_black() {
+    let lines = session("xboard\nprotover 2\nnew\nsd 2\nusermove e2e4\nusermove g1f3\n");
+    let moves = engine_moves(&lines);
+    assert_eq!(moves.len(), 2);
+
+    // both answers are legal in the game as played
+    let mut board = Board::build();
+    for text in ["e2e4", moves[0], "g1f3", moves[1]] {
+        let mv = parse_uci_move(&board, text).unwrap();
+        board.make_move(mv);
+    }
+}
+
+#[test]
+fn force_mode_and_go() {
+    let lines = session("new\nsd 2\nforce\nusermove e2e4\nusermove e7e5\ngo\n");
+    let moves = engine_moves(&lines);
+    assert_eq!(moves.len(), 1);
+
+    let mut board = Board::build();
+    for text in ["e2e4", "e7e5", moves[0]] {
+        let mv = parse_uci_move(&board, text).unwrap();
+        board.make_move(mv);
+    }
+}
+
+#[test]
+fn illegal_moves_and_positions() {
+    let lines = session("new\nforce\nusermove e2e5\nsetboard 8/8/8 w - - 0 1\nfoo\n");
+    assert_eq!(lines[0], "Illegal move: e2e5");
+    assert!(lines[1].starts_with("tellusererror Illegal position"));
+    assert!(lines[2].starts_with("Error (unknown command)"));
+}
+
+#[test]
+fn undo_and_remove() {
+    let buffer = SharedBuffer::default();
+    let mut engine = XboardEngine::new(buffer.clone());
+    for line in ["new", "sd 2", "force", "usermove e2e4", "usermove e7e5", "usermove g1f3", "undo", "remove", "usermove d2d4", "go"] {
+        assert!(engine.handle(line));
+    }
+    engine.finish_search();
+    let lines = buffer.lines();
+    let moves = engine_moves(&lines);
+
+    let mut board = Board::build();
+    for text in ["d2d4", moves[0]] {
+        let mv = parse_uci_move(&board, text).unwrap();
+        board.make_move(mv);
+    }
+}
+
+#[test]
+fn checkmate_is_announced() {
+    let lines = session("setboard 7k/5Q2/6K1/8/8/8/8/8 w - - 0 1\nforce\nusermove f7g7\n");
+    assert_eq!(lines, ["1-0 {White mates}"]);
+}
+
+#[test]
+fn clock_commands() {
+    assert_eq!(
+        parse_level(&["40", "5", "0"]),
+        Some(TimeControl { moves_per_session: Some(40), base: 300_000, ..TimeControl::default() })
+    );
+    assert_eq!(
+        parse_level(&["0", "2:30", "1.5"]),
+        Some(TimeControl { base: 150_000, increment: 1500, ..TimeControl::default() })
+    );
+    assert_eq!(parse_level(&["0", "x", "1"]), None);
+
+    let mut engine = XboardEngine::new(vec![]);
+    for line in ["new", "force", "level 40 5 2", "sd 6", "st 3", "time 12000", "otim 9000"] {
+        engine.handle(line);
+    }
+    assert_eq!(
+        engine.limits(),
+        Go {
+            wtime: Some(120_000),
+            btime: Some(90_000),
+            winc: Some(2000),
+            binc: Some(2000),
+            movestogo: Some(40),
+            depth: Some(6),
+            movetime: Some(3000),
+            ..Go::default()
+        }
+    );
+}
+
+#[test]
+fn thinking_output() {
+    let lines = session("new\nsd 2\npost\nforce\nusermove e2e4\ngo\n");
+    let thinking: Vec<&String> = lines.iter().filter(|line| !line.starts_with("move ")).collect();
+    assert_eq!(thinking.len(), 2);
+    for (depth, line) in thinking.iter().enumerate() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        assert_eq!(fields[0], (depth + 1).to_string());
+        assert!(fields[1..4].iter().all(|field| field.parse::<i64>().is_ok()), "{}", line);
+        assert!(fields.len() > 4);
+    }
+}
+
+#[test]
+fn move_now_interrupts_the_search() {
+    // thirty seconds a move, but ? asks for the move at once
+    let started = Instant::now();
+    let lines = session("new\nst 30\nforce\nusermove e2e4\ngo\n?\n");
+    assert!(started.elapsed() < Duration::from_secs(10));
+
+    let moves = engine_moves(&lines);
+    assert_eq!(moves.len(), 1);
+    let mut board = Board::build();
+    for text in ["e2e4", moves[0]] {
+        let mv = parse_uci_move(&board, text).unwrap();
+        board.make_move(mv);
+    }
+}
+
+#[test]
+fn force_abandons_the_search() {
+    let started = Instant::now();
+    let lines = session("new\nst 30\nforce\nusermove e2e4\ngo\nforce\nusermove e7e5\nping 1\n");
+    assert!(started.elapsed() < Duration::from_secs(10));
+
+    // no move from the engine, and black's move went onto the board it was thinking about
+    assert_eq!(lines, ["pong 1"]);
+}
+
+#[test]
+fn new_goes_back_to_standard_chess() {
+    // castling is mate, and a chess960 engine has to send it as O-O
+    let castling = "setboard 4rkr1/4p1p1/8/8/8/8/8/6KR w K - 0 1\nsd 2\ngo\n";
+    let lines = session(&format!("new\nvariant fischerandom\n{}", castling));
+    assert_eq!(engine_moves(&lines), ["O-O"]);
+
+    // xboard repeats the variant after new, without it the game is standard chess again
+    let lines = session(&format!("new\nvariant fischerandom\nnew\n{}", castling));
+    // the king already stands on g1, so castling goes out as it taking the rook
+    let moves = engine_moves(&lines);
+    assert_eq!(moves, ["g1h1"]);
+    let board = Board::build_from_fen("4rkr1/4p1p1/8/8/8/8/8/6KR w K - 0 1".to_string()).unwrap();
+    assert_eq!(parse_uci_move(&board, moves[0]).unwrap().castle, Some(CastleSide::KingSide));
+    let lines = session(&format!("new\nvariant fischerandom\nnew\nvariant fischerandom\n{}", castling));
+    assert_eq!(engine_moves(&lines), ["O-O"]);
+}
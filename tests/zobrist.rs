@@ -0,0 +1,60 @@
+use chess_engine::{
+    components::chess::Board,
+    fen::fen::get_square_from_string,
+    moves::moves::get_legal_moves,
+    zobrist::zobrist::compute_hash,
+};
+
+// reference keys from the Polyglot book format description
+const REFERENCE: [(&[&str], u64); 9] = [
+    (&[], 0x463b96181691fc9c),
+    (&["e2e4"], 0x823c9b50fd114196),
+    (&["e2e4", "d7d5"], 0x0756b94461c50fb0),
+    (&["e2e4", "d7d5", "e4e5"], 0x662fafb965db29d4),
+    (&["e2e4", "d7d5", "e4e5", "f7f5"], 0x22a48b5a8e47ff78),
+    (&["e2e4", "d7d5", "e4e5", "f7f5", "e1e2"], 0x652a607ca3f242c1),
+    (&["e2e4", "d7d5", "e4e5", "f7f5", "e1e2", "e8f7"], 0x00fdd303c946bdd9),
+    (&["a2a4", "b7b5", "h2h4", "b5b4", "c2c4"], 0x3c8123ea7b067637),
+    (&["a2a4", "b7b5", "h2h4", "b5b4", "c2c4", "b4c3", "a1a3"], 0x5c3f9b829b279560),
+];
+
+fn play(board: &mut Board, mv: &str) {
+    let source = get_square_from_string(&mv[..2]);
+    let destination = get_square_from_string(&mv[2..4]);
+    let mv = get_legal_moves(board)
+        .into_iter()
+        .find(|m| Some(m.source) == source && Some(m.destination) == destination)
+        .unwrap();
+    board.make_move(mv);
+}
+
+#[test]
+fn polyglot_reference_keys() {
+    for (moves, key) in REFERENCE {
+        let mut board = Board::build();
+        for mv in moves {
+            play(&mut board, mv);
+        }
+        assert_eq!(board.polyglot_key(), key, "after {:?}", moves);
+    }
+}
+
+#[test]
+fn incremental_hash_matches_full_computation() {
+    let mut board = Board::build_from_fen(
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1".to_string(),
+    )
+    .unwrap();
+    let start = board.hash;
+    for mv in get_legal_moves(&board) {
+        board.make_move(mv);
+        assert_eq!(board.hash, compute_hash(&board), "{:?}", mv);
+        for reply in get_legal_moves(&board) {
+            board.make_move(reply);
+            assert_eq!(board.hash, compute_hash(&board), "{:?} {:?}", mv, reply);
+            board.unmake_move();
+        }
+        board.unmake_move();
+    }
+    assert_eq!(board.hash, start);
+}